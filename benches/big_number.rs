@@ -0,0 +1,57 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use orbital_system::big_number::format_policy::FormatPolicy;
+use orbital_system::big_number::{BigNumber, BigVec2};
+// Each arithmetic benchmark is run twice: as the value is now, and followed by writing the
+// result out, which is what every operation used to pay when BigNumber kept its own string
+fn arithmetic(c: &mut Criterion) {
+    let a: BigNumber = "1.496x10^11".parse().unwrap();
+    let b: BigNumber = "-7.7x10^8".parse().unwrap();
+    let policy = FormatPolicy::default();
+    let mut group = c.benchmark_group("arithmetic");
+    group.bench_function("add", |bench| bench.iter(|| black_box(a) + black_box(b)));
+    group.bench_function("add_and_format", |bench| {
+        bench.iter(|| policy.format(&(black_box(a) + black_box(b))))
+    });
+    group.bench_function("mul", |bench| bench.iter(|| black_box(a) * black_box(b)));
+    group.bench_function("mul_and_format", |bench| {
+        bench.iter(|| policy.format(&(black_box(a) * black_box(b))))
+    });
+    group.bench_function("increase_power", |bench| {
+        bench.iter(|| {
+            let mut number = black_box(a);
+            number.increase_power(3).unwrap();
+            number
+        })
+    });
+    group.bench_function("increase_power_and_format", |bench| {
+        bench.iter(|| {
+            let mut number = black_box(a);
+            number.increase_power(3).unwrap();
+            policy.format(&number)
+        })
+    });
+    group.finish();
+}
+// One frame of orbit updates for a few hundred bodies
+fn frame(c: &mut Criterion) {
+    let scale: BigNumber = "1M".parse().unwrap();
+    let positions: Vec<BigVec2> = (0..500)
+        .map(|index| {
+            BigVec2::new(
+                BigNumber::new_d(index as f32 * 1.5),
+                BigNumber::new_d(-(index as f32)),
+            ) * scale
+        })
+        .collect();
+    let velocity = BigVec2::new(BigNumber::new_d(29.78), BigNumber::new_d(-0.5));
+    c.bench_function("frame_of_500_bodies", |bench| {
+        bench.iter(|| {
+            positions
+                .iter()
+                .map(|position| *position + velocity.scale(black_box(scale)))
+                .collect::<Vec<BigVec2>>()
+        })
+    });
+}
+criterion_group!(benches, arithmetic, frame);
+criterion_main!(benches);
@@ -1,16 +1,28 @@
-use num::{
-    complex::ComplexFloat,
-    traits::{
-        float::{FloatCore, TotalOrder},
-        real::Real,
-        Pow,
-    },
-    Float,
-};
+use macroquad::math::{vec2, vec3, Vec2, Vec3};
+use num::traits::{Float, ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
 use std::{
-    cmp::{self, Ordering},
-    ops::{self, BitAndAssign},
+    cmp::Ordering,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    num::IntErrorKind,
+    ops,
+    str::FromStr,
 };
+pub mod decimal;
+pub mod format_policy;
+mod math;
+mod overflow;
+#[cfg(test)]
+mod properties;
+pub mod reduce;
+pub mod serialization;
+pub mod suffix_table;
+mod traits;
+pub mod uncertain;
+use overflow::Unbounded;
+use suffix_table::active_table;
 const HAVEN_ABBREVIATIONS: [Option<&str>; 9] = [
     None,
     Some("K"),
@@ -22,547 +34,1421 @@ const HAVEN_ABBREVIATIONS: [Option<&str>; 9] = [
     Some("SX"),
     Some("SP"),
 ];
-const ROUNDING_ORDER: f32 = 2.0;
-fn bool_from_number(number: i32) -> bool {
-    match number {
-        -1 => false,
-        0 => false,
-        _ => true,
-    }
-}
+// SI prefixes from quecto (10^-30) to quetta (10^30), one per power of a thousand
+const SI_PREFIXES: [&str; 21] = [
+    "q", "r", "y", "z", "a", "f", "p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E", "Z", "Y",
+    "R", "Q",
+];
+const SI_PREFIX_OFFSET: i32 = -30;
 fn sign_from_bool(boolean: bool) -> i32 {
     match boolean {
         false => -1,
         true => 1,
     }
 }
-fn reverse_number(a: i32, b: i32, c: i32) -> i32 {
-    *(a..=b)
-        .rev()
-        .collect::<Vec<i32>>()
-        .get((c - a) as usize)
-        .unwrap_or(&0)
-}
-fn cyclic_wrap(a: i32, b: i32, max: i32) -> f32 {
-    let modulo_of_sum = (a + b) % max;
-    Real::powf(
-        10.0,
-        match (modulo_of_sum != 0) {
-            true => modulo_of_sum,
-            false => max,
-        } as f32,
-    )
-}
-fn exponential_modulo_ten(a: f32, b: f32) -> i32 {
-    let modulus = (a.log10().floor() - b.log10().floor()) % 3.0;
-    let map = match modulus as i32 {
-        0 => 0,
-        1 => 1,
-        _ => -1,
+// Float types a `BigNumber` can keep its mantissa in. Operations on mantissas are worked out
+// in `Wide` and rounded back once: f64 for the binary floats, the type itself for `Decimal`
+pub trait Mantissa: Float + fmt::Debug + fmt::Display + FromStr + Default {
+    type Wide: Mantissa;
+    fn widen(self) -> Self::Wide;
+    fn narrow(wide: Self::Wide) -> Self;
+}
+impl Mantissa for f32 {
+    type Wide = f64;
+    fn widen(self) -> f64 {
+        self as f64
+    }
+    fn narrow(wide: f64) -> f32 {
+        wide as f32
+    }
+}
+impl Mantissa for f64 {
+    type Wide = f64;
+    fn widen(self) -> f64 {
+        self
+    }
+    fn narrow(wide: f64) -> f64 {
+        wide
+    }
+}
+fn from_f64<T: Float>(number: f64) -> T {
+    num::cast(number).unwrap()
+}
+fn get_first_significant_figure<T: Float>(number: T) -> T {
+    number / from_f64::<T>(10.0).powf(number.log10().floor())
+}
+// Haven suffixes only cover whole thousands from 10^0 up to the last suffix of the active table
+fn is_haven_exponent(exponent: i32) -> bool {
+    active_table().suffix(exponent).is_some()
+}
+// The multiple of 3 at or below `exponent` that Engineering and SI write it with. Nothing is
+// below i32::MIN, so the few exponents just above it are written as they are
+fn engineering_exponent(exponent: i32) -> i32 {
+    exponent
+        .checked_sub(exponent.rem_euclid(3))
+        .unwrap_or(exponent)
+}
+// Returns the sign, the unsigned rest and the byte offset where that rest starts
+fn split_sign(x: &str) -> (bool, &str, usize) {
+    let trimmed = x.trim();
+    let (is_positive, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => (false, rest),
+        None => (true, trimmed.strip_prefix('+').unwrap_or(trimmed)),
     };
-    map
+    let start = (x.len() - x.trim_start().len()) + (trimmed.len() - unsigned.len());
+    (is_positive, unsigned, start)
 }
-fn get_first_significant_figure(number: f32) -> f32 {
-    number / Real::powf(10.0, number.log10().floor())
+// Anything with a power of ten ("x10^" or "e") is read as Scientific, everything else as Haven
+fn is_scientific(x: &str) -> bool {
+    let (_, unsigned, _) = split_sign(x);
+    Scientific::split(unsigned).is_ok()
 }
-fn round_to_order(number: f32) -> f32 {
-    (number * Real::powf(10.0, ROUNDING_ORDER)).floor() / Real::powf(10.0, ROUNDING_ORDER)
+// Picks the format a written number is in: a word the suffix table does not know, set apart
+// by a space, is an SI prefix and unit ("149.6 Gm"), and a power of ten on a mantissa with
+// more than one integer digit is Engineering ("149.6x10^6")
+fn detect_format(x: &str) -> Format {
+    let (_, unsigned, _) = split_sign(x);
+    let is_unknown_suffix = matches!(
+        Haven::split(unsigned),
+        Err(BigNumberError {
+            cause: ErrorCause::UnknownSuffix,
+            ..
+        })
+    );
+    if (is_unknown_suffix && SI::is_spaced(unsigned)) {
+        return Format::SI(x.to_string());
+    }
+    match Scientific::split(unsigned) {
+        Ok((number, _)) if (number.split('.').next().unwrap_or("").len() > 1) => {
+            Format::Engineering(x.to_string())
+        }
+        Ok(_) => Format::Scientific(x.to_string()),
+        Err(_) => Format::Haven(x.to_string()),
+    }
 }
-fn is_between_integers(a: f32, b: i32, c: i32) -> bool {
-    (a > b as f32) && (a < c as f32)
+fn serialize_parts<T: Mantissa>(base: T, exponent: i32) -> Format {
+    if (base.is_zero()) {
+        return Format::Haven(("0.0").to_string());
+    }
+    if (base.is_nan()) {
+        return Format::Haven("NaN".to_string());
+    }
+    if (base.is_infinite()) {
+        return Format::Haven("inf".to_string());
+    }
+    serialize_mantissa(&get_first_significant_figure(base).to_string(), exponent)
+}
+// Same as `serialize_parts` for a mantissa that is already written out as "d.ddd"
+fn serialize_mantissa(mantissa: &str, exponent: i32) -> Format {
+    match is_haven_exponent(exponent) {
+        true => Format::Haven(Haven::write(mantissa, exponent)),
+        false => Format::Scientific(Scientific::write(mantissa, exponent)),
+    }
+}
+// Writes a mantissa given as "d.ddd" in `notation`, without the sign
+fn write_mantissa(mantissa: &str, exponent: i32, notation: &Notation) -> Format {
+    match notation {
+        Notation::Haven => serialize_mantissa(mantissa, exponent),
+        Notation::Scientific => Format::Scientific(Scientific::write(mantissa, exponent)),
+        Notation::Engineering => Format::Engineering(Engineering::write(mantissa, exponent)),
+        Notation::SI(unit) => Format::SI(SI::write(mantissa, exponent) + unit),
+    }
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Format {
     Haven(String),
     Scientific(String),
+    // Scientific with the exponent a multiple of 3, "149.6x10^6"
+    Engineering(String),
+    // SI prefix followed by a unit, "149.6 Gm"
+    SI(String),
+}
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Haven(x) | Format::Scientific(x) | Format::Engineering(x) | Format::SI(x) => {
+                write!(f, "{}", x)
+            }
+        }
+    }
+}
+// Which format to write a number in, see `BigNumber::to_format`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Notation {
+    Haven,
+    Scientific,
+    Engineering,
+    // The unit written after the prefix, e.g. "m" for "149.6 Gm"
+    SI(String),
 }
-#[derive(Debug, Clone)]
-pub struct BigNumber {
-    pub serialized: Format,
-    pub base: f32,
+// A plain value, written out only when it is displayed, see `FormatPolicy`
+#[derive(Debug, Clone, Copy)]
+pub struct BigNumber<T: Mantissa = f32> {
+    pub base: T,
     pub exponent: i32,
     pub is_positive: bool,
 }
-macro_rules! handle_analysis_errors {
-    ($condition:expr, $error:expr) => {
-        if ($condition) {
-            match $error.err().unwrap() {
-                AnalysisErrors::InvalidPrefix => {
-                    println!("BRO");
-                }
-                AnalysisErrors::InvalidSuffix => {
-                    println!("BRUH");
-                }
-                AnalysisErrors::InvalidExponent => {
-                    println!("DUDE");
-                }
-            }
-            return None;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCause {
+    // Haven
+    BadPrefix,
+    UnknownSuffix,
+    //
+    BadExponent,
+    ExponentOverflow,
+    ExponentUnderflow,
+    DivisionByZero,
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigNumberError {
+    pub input: String,
+    // Character offset into `input`, when the error comes from reading text
+    pub offset: Option<usize>,
+    pub cause: ErrorCause,
+}
+impl BigNumberError {
+    // Parsing helpers only see part of the input, so they report byte offsets
+    // into that part and `within` turns them into characters of the whole input
+    fn new(cause: ErrorCause, input: &str, offset: Option<usize>) -> BigNumberError {
+        BigNumberError {
+            input: input.to_string(),
+            offset,
+            cause,
         }
-    };
+    }
+    fn within(self, input: &str, start: usize) -> BigNumberError {
+        BigNumberError {
+            input: input.to_string(),
+            offset: self
+                .offset
+                .map(|offset| input[..start + offset].chars().count()),
+            cause: self.cause,
+        }
+    }
 }
-impl BigNumber {
-    pub fn new(serialized: Format) -> Option<BigNumber> {
-        let mut big_number = BigNumber::new_d(1.0);
-        let inner = big_number.get_value();
-        let exponent = match serialized {
-            Format::Haven(_) => Haven::get_exponent(inner.clone()),
-            Format::Scientific(_) => Scientific::get_exponent(inner.clone()),
-        }
-        .ok()
-        .unwrap();
-        let multiplier = match serialized {
-            Format::Haven(_) => Haven::get_multiplier(inner, exponent),
-            Format::Scientific(_) => Scientific::get_multiplier(inner, exponent),
-        };
-        handle_analysis_errors!(multiplier.is_err(), multiplier);
-        big_number.increase_power(exponent, false);
+impl fmt::Display for ErrorCause {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ErrorCause::BadPrefix => write!(f, "malformed number"),
+            ErrorCause::UnknownSuffix => write!(f, "unknown Haven suffix"),
+            ErrorCause::BadExponent => write!(f, "malformed exponent"),
+            ErrorCause::ExponentOverflow => write!(f, "exponent overflow"),
+            ErrorCause::ExponentUnderflow => write!(f, "exponent underflow"),
+            ErrorCause::DivisionByZero => write!(f, "division by zero"),
+        }
+    }
+}
+impl fmt::Display for BigNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} in \"{}\"", self.cause, self.input)?;
+        match self.offset {
+            Some(offset) => write!(f, " at character {}", offset),
+            None => Ok(()),
+        }
+    }
+}
+impl Error for BigNumberError {}
+impl<T: Mantissa> BigNumber<T> {
+    pub fn new(serialized: Format) -> Result<BigNumber<T>, BigNumberError> {
         match serialized {
-            Format::Haven(_) => {
-                big_number.serialized =
-                    Format::Haven(Haven::create(multiplier.ok().unwrap(), exponent, false));
-            }
-            Format::Scientific(_) => {
-                big_number.serialized = Format::Scientific(Scientific::create(
-                    multiplier.ok().unwrap(),
-                    exponent,
-                    false,
-                ));
-            }
+            Format::Haven(ref x) => BigNumber::parse_with::<Haven>(x),
+            Format::Scientific(ref x) => BigNumber::parse_with::<Scientific>(x),
+            Format::Engineering(ref x) => BigNumber::parse_with::<Engineering>(x),
+            Format::SI(ref x) => BigNumber::parse_with::<SI>(x),
         }
-        Some(big_number)
     }
-    pub fn new_d(deserialized: f32) -> BigNumber {
-        let positive_deserialized = deserialized.abs();
-        let mut temp = BigNumber {
-            serialized: Format::Haven(("1.0").to_string()),
-            base: 1.0,
-            exponent: 0,
-            is_positive: (deserialized >= 0.0),
-        };
-        if (deserialized == 0.0) {
-            return BigNumber {
-                serialized: Format::Haven(("0.0").to_string()),
-                base: 0.0,
-                exponent: 0,
-                ..Default::default()
-            };
+    fn parse_with<N: NumberMethods>(x: &str) -> Result<BigNumber<T>, BigNumberError> {
+        let (is_positive, unsigned, start) = split_sign(x);
+        if (unsigned.eq_ignore_ascii_case("nan")) {
+            return Ok(BigNumber::nan());
         }
-        if (is_between_integers(positive_deserialized, 0, 1)) {
-            let base = get_first_significant_figure(positive_deserialized);
-            let exponent = -((1.0 / positive_deserialized).log10().ceil()) as i32;
-            return BigNumber {
-                serialized: Format::Scientific(Scientific::create(base, exponent, false)),
-                base,
-                exponent,
-                is_positive: (deserialized >= 0.0),
-            };
+        if (["inf", "infinity", "∞"]
+            .iter()
+            .any(|infinity| unsigned.eq_ignore_ascii_case(infinity)))
+        {
+            return Ok(match is_positive {
+                true => BigNumber::infinity(),
+                false => BigNumber::neg_infinity(),
+            });
+        }
+        let mut base: T =
+            N::get_multiplier(unsigned.to_string()).map_err(|error| error.within(x, start))?;
+        let mut exponent =
+            N::get_exponent(unsigned.to_string()).map_err(|error| error.within(x, start))?;
+        // A mantissa with more digits than the float holds can round up to 10
+        if (base >= from_f64(10.0)) {
+            base = base / from_f64(10.0);
+            exponent = exponent
+                .checked_add(1)
+                .ok_or_else(|| BigNumberError::new(ErrorCause::ExponentOverflow, x, None))?;
         }
-        if (positive_deserialized < 10.0) {
+        if (base.is_zero()) {
+            return Ok(BigNumber::new_d(T::zero()));
+        }
+        Ok(BigNumber {
+            base,
+            exponent,
+            is_positive,
+        })
+    }
+    pub fn new_d(deserialized: T) -> BigNumber<T> {
+        if (deserialized.is_zero()) {
             return BigNumber {
-                serialized: Format::Haven((positive_deserialized as f32).to_string()),
-                base: positive_deserialized,
+                base: T::zero(),
                 exponent: 0,
-                is_positive: (deserialized >= 0.0),
+                is_positive: true,
             };
         }
-        temp.increase_power(positive_deserialized.log10().floor() as i32, false);
-        match temp.serialized {
-            Format::Haven(x) => {
-                temp.base = get_first_significant_figure(positive_deserialized);
-                temp.serialized =
-                    Format::Haven(Haven::create(temp.base, temp.exponent as i32, false));
-            }
-            Format::Scientific(x) => {
-                let current_multiplier =
-                    Scientific::get_multiplier(x, positive_deserialized.log10().floor() as i32);
-                temp.base = current_multiplier.ok().unwrap();
-                temp.serialized = Format::Scientific(Scientific::create(
-                    temp.base,
-                    positive_deserialized.log10().floor() as i32,
-                    false,
-                ));
-            }
+        BigNumber::from_parts(deserialized.widen(), 0)
+    }
+    // NaN and the infinities keep the float value as their base and an exponent of 0
+    fn special(base: T, is_positive: bool) -> BigNumber<T> {
+        BigNumber {
+            base,
+            exponent: 0,
+            is_positive,
         }
-        temp
     }
-    pub fn get_value(&self) -> String {
-        match self.serialized.clone() {
-            Format::Haven(x) => x,
-            Format::Scientific(x) => x,
+    pub fn nan() -> BigNumber<T> {
+        BigNumber::special(T::nan(), true)
+    }
+    pub fn infinity() -> BigNumber<T> {
+        BigNumber::special(T::infinity(), true)
+    }
+    pub fn neg_infinity() -> BigNumber<T> {
+        BigNumber::special(T::infinity(), false)
+    }
+    pub fn is_nan(&self) -> bool {
+        self.base.is_nan()
+    }
+    pub fn is_infinite(&self) -> bool {
+        self.base.is_infinite()
+    }
+    pub fn is_finite(&self) -> bool {
+        self.base.is_finite()
+    }
+    // The canonical form of a number whose fields were set by hand: a mantissa in [1, 10),
+    // a single positive zero, and NaN or the infinities with an exponent of 0
+    pub fn normalize(self) -> BigNumber<T> {
+        match self.is_finite() {
+            true => BigNumber::from_parts(self.signed_base(), self.exponent),
+            false => BigNumber::from_parts(self.signed_base(), 0),
         }
     }
-    pub fn increase_power(&mut self, increment: i32, is_bounce: bool) -> Option<bool> {
-        if (increment == 0) {
-            return Some(true);
+    // Builds a normalized number out of a mantissa that may have left [1, 10) after an operation.
+    // Exponents past i32 become infinite or zero, see `overflow`
+    fn from_parts(signed_base: T::Wide, exponent: i32) -> BigNumber<T> {
+        Unbounded::new(signed_base, exponent as i64).to_big_number()
+    }
+    // What IEEE floats give when NaN or an infinity is involved. Finite operands only
+    // matter through their sign and whether they are zero, so they stand in as 0 or ±1.
+    fn non_finite_operation(
+        &self,
+        other: &BigNumber<T>,
+        operation: fn(T::Wide, T::Wide) -> T::Wide,
+    ) -> Option<Unbounded<T::Wide>> {
+        if (self.is_finite() && other.is_finite()) {
+            return None;
         }
-        let exponent = match self.serialized.clone() {
-            Format::Haven(x) => Haven::get_exponent(x),
-            Format::Scientific(x) => Scientific::get_exponent(x),
+        let stand_in = |number: &BigNumber<T>| match (number.is_finite() && !number.is_zero()) {
+            true => number.signed_base().signum(),
+            false => number.signed_base(),
         };
-        handle_analysis_errors!(exponent.is_err(), exponent);
-        let mut flag = false;
-        let new_power = match (is_bounce && (self.exponent + increment) < 0) {
-            false => self.exponent + increment,
-            true => {
-                flag = true;
-                (self.exponent + increment).abs()
-            }
+        Some(Unbounded::new(
+            operation(stand_in(self), stand_in(other)),
+            0,
+        ))
+    }
+    fn signed_base(&self) -> T::Wide {
+        match self.is_positive {
+            true => self.base.widen(),
+            false => -self.base.widen(),
+        }
+    }
+    pub fn is_zero(&self) -> bool {
+        self.base.is_zero()
+    }
+    // Converts the mantissa through its written digits, so 1.1_f32 becomes 1.1_f64
+    // rather than 1.100000023841858
+    pub fn cast<U: Mantissa>(&self) -> BigNumber<U> {
+        let mut base = match self.base.to_string().parse::<U>() {
+            Ok(base) => base,
+            Err(_) => from_f64(self.base.to_f64().unwrap()),
         };
-        if ((new_power > (HAVEN_ABBREVIATIONS.len() * 3) as i32) || (flag)) {
-            let multiplier = Scientific::get_multiplier(self.get_value(), increment);
-            handle_analysis_errors!(multiplier.is_err(), multiplier);
-            self.exponent = new_power;
-            self.serialized = Format::Scientific(Scientific::create(self.base, new_power, false));
-        } else {
-            let multiplier = Haven::get_multiplier(self.get_value(), new_power);
-            handle_analysis_errors!(multiplier.is_err(), multiplier);
-            self.exponent = new_power;
-            self.serialized = Format::Haven(Haven::create(self.base, new_power, false));
+        let mut exponent = self.exponent;
+        if (base >= from_f64(10.0)) {
+            base = base / from_f64(10.0);
+            exponent = exponent.saturating_add(1);
+        }
+        BigNumber {
+            base,
+            exponent,
+            is_positive: self.is_positive,
         }
-        Some(true)
     }
-    pub fn decrease_power(&mut self, increment: i32) -> Option<bool> {
-        self.increase_power(-increment, false)
+    // The number written with the display policy, without its sign
+    pub fn get_value(&self) -> String {
+        format_policy::display_policy().format(&self.abs())
+    }
+    // The number written in `notation`, without its sign
+    pub fn to_format(self, notation: &Notation) -> Format {
+        if (!self.is_finite()) {
+            return serialize_parts(self.base, self.exponent);
+        }
+        match notation {
+            Notation::Haven => Haven::serialize(self.base, self.exponent),
+            Notation::Scientific => Scientific::serialize(self.base, self.exponent),
+            Notation::Engineering => Engineering::serialize(self.base, self.exponent),
+            Notation::SI(unit) => match self.is_zero() {
+                true => Format::SI(format!("0 {}", unit)),
+                false => Format::SI(SI::serialize(self.base, self.exponent).to_string() + unit),
+            },
+        }
+    }
+    fn exponent_overflow(&self) -> BigNumberError {
+        BigNumberError::new(ErrorCause::ExponentOverflow, &self.to_string(), None)
+    }
+    pub fn increase_power(&mut self, increment: i32) -> Result<(), BigNumberError> {
+        if (increment == 0 || !self.is_finite()) {
+            return Ok(());
+        }
+        self.exponent = self
+            .exponent
+            .checked_add(increment)
+            .ok_or_else(|| self.exponent_overflow())?;
+        Ok(())
+    }
+    pub fn decrease_power(&mut self, increment: i32) -> Result<(), BigNumberError> {
+        self.increase_power(
+            increment
+                .checked_neg()
+                .ok_or_else(|| self.exponent_overflow())?,
+        )
     }
 }
-impl Default for BigNumber {
+impl<T: Mantissa> Default for BigNumber<T> {
     fn default() -> Self {
-        BigNumber {
-            serialized: Format::Haven("1.0".to_string()),
-            base: 1.0,
-            exponent: 0,
-            is_positive: true,
-        }
+        BigNumber::new_d(T::zero())
+    }
+}
+impl From<BigNumber<f32>> for BigNumber<f64> {
+    fn from(number: BigNumber<f32>) -> BigNumber<f64> {
+        number.cast()
+    }
+}
+impl<T: Mantissa> fmt::Display for BigNumber<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_with(&format_policy::display_policy()))
     }
 }
-impl PartialEq for BigNumber {
+impl<T: Mantissa> FromStr for BigNumber<T> {
+    type Err = BigNumberError;
+    fn from_str(s: &str) -> Result<BigNumber<T>, BigNumberError> {
+        BigNumber::new(detect_format(s))
+    }
+}
+// Equality and ordering are total: NaN equals itself and sorts above +inf
+impl<T: Mantissa> PartialEq for BigNumber<T> {
     fn eq(&self, other: &Self) -> bool {
-        ((self.is_positive == other.is_positive)
-            && (self.base == other.base)
-            && (self.exponent == other.exponent))
+        self.cmp(other) == Ordering::Equal
     }
 }
-impl Eq for BigNumber {}
-impl PartialOrd for BigNumber {
+impl<T: Mantissa> Eq for BigNumber<T> {}
+impl<T: Mantissa> PartialOrd for BigNumber<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let mut result = None;
-        result = match self.exponent.partial_cmp(&other.exponent) {
-            Some(Ordering::Equal) => self.base.partial_cmp(&other.base),
-            Some(x) => Some(x),
-            None => result,
+        Some(self.cmp(other))
+    }
+}
+impl<T: Mantissa> Ord for BigNumber<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+        let sign = |number: &BigNumber<T>| match (number.is_zero(), number.is_positive) {
+            (true, _) => 0,
+            (false, true) => 1,
+            (false, false) => -1,
         };
-        result
+        if (sign(self) != sign(other)) {
+            return sign(self).cmp(&sign(other));
+        }
+        // Both zero, whatever their exponents
+        if (sign(self) == 0) {
+            return Ordering::Equal;
+        }
+        let magnitude = match (self.is_infinite(), other.is_infinite()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.exponent.cmp(&other.exponent).then(
+                self.base
+                    .partial_cmp(&other.base)
+                    .unwrap_or(Ordering::Equal),
+            ),
+        };
+        match self.is_positive {
+            true => magnitude,
+            false => magnitude.reverse(),
+        }
+    }
+}
+impl<T: Mantissa> Hash for BigNumber<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if (self.is_nan()) {
+            return state.write_u8(0);
+        }
+        // Every zero is equal, whatever its sign, mantissa bits or exponent
+        if (self.is_zero()) {
+            return state.write_u8(1);
+        }
+        self.is_positive.hash(state);
+        self.base.integer_decode().hash(state);
+        if (self.is_finite()) {
+            self.exponent.hash(state);
+        }
     }
-    fn lt(&self, other: &Self) -> bool {
-        (self.partial_cmp(other).unwrap() == Ordering::Less)
+}
+impl<T: Mantissa> ops::Neg for BigNumber<T> {
+    type Output = BigNumber<T>;
+    fn neg(self) -> BigNumber<T> {
+        let mut negated = self;
+        if (!negated.is_zero() && !negated.is_nan()) {
+            negated.is_positive = !negated.is_positive;
+        }
+        negated
     }
-    fn le(&self, other: &Self) -> bool {
-        ((self.lt(other)) || (self.partial_cmp(other).unwrap() == Ordering::Equal))
+}
+// The operations with the exponent kept in an i64, before it is checked against the i32 range
+impl<T: Mantissa> BigNumber<T> {
+    fn unbounded_add(self, other: BigNumber<T>) -> Unbounded<T::Wide> {
+        if let Some(result) = self.non_finite_operation(&other, |a, b| a + b) {
+            return result;
+        }
+        if (self.is_zero()) {
+            return Unbounded::from(other);
+        }
+        if (other.is_zero()) {
+            return Unbounded::from(self);
+        }
+        // Both mantissas are shifted into the larger exponent, so a sum that
+        // crosses zero simply comes out negative
+        let exponent = self.exponent.max(other.exponent);
+        let ten = from_f64::<T::Wide>(10.0);
+        let sum = self.signed_base() * ten.powi(self.exponent.saturating_sub(exponent))
+            + other.signed_base() * ten.powi(other.exponent.saturating_sub(exponent));
+        Unbounded::new(sum, exponent as i64)
     }
-    fn gt(&self, other: &Self) -> bool {
-        (self.partial_cmp(other).unwrap() == Ordering::Greater)
+    fn unbounded_sub(self, other: BigNumber<T>) -> Unbounded<T::Wide> {
+        self.unbounded_add(-other)
     }
-    fn ge(&self, other: &Self) -> bool {
-        ((self.gt(other)) || (self.partial_cmp(other).unwrap() == Ordering::Equal))
+    fn unbounded_mul(self, other: BigNumber<T>) -> Unbounded<T::Wide> {
+        if let Some(result) = self.non_finite_operation(&other, |a, b| a * b) {
+            return result;
+        }
+        Unbounded::new(
+            self.signed_base() * other.signed_base(),
+            self.exponent as i64 + other.exponent as i64,
+        )
     }
-}
-impl Ord for BigNumber {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-    fn max(self, other: Self) -> Self
-    where
-        Self: Sized,
-    {
-        match (self > other) {
-            false => other,
-            true => self,
-        }
-    }
-    fn min(self, other: Self) -> Self
-    where
-        Self: Sized,
-    {
-        match (self < other) {
-            false => other,
-            true => self,
-        }
-    }
-    fn clamp(self, min: Self, max: Self) -> Self
-    where
-        Self: Sized,
-        Self: PartialOrd,
-    {
-        (self.min(max)).max(min)
-    }
-}
-impl ops::Add<f32> for BigNumber {
-    type Output = BigNumber;
-    fn add(self, rhs: f32) -> BigNumber {
-        let big_version = BigNumber::new_d(rhs);
-        self + big_version
-    }
-}
-impl ops::Add for BigNumber {
-    type Output = BigNumber;
-    fn add(self, other: BigNumber) -> BigNumber {
-        let mut sum = BigNumber::max(self.clone(), other.clone());
-        let mut other_min = BigNumber::min(self.clone(), other.clone());
-        let base_delta =
-            other_min.base / Real::powi(10.0, sum.exponent - other_min.exponent) as f32;
-        let new_base = (sum.base * sign_from_bool(sum.is_positive) as f32)
-            + (sign_from_bool(other_min.is_positive) as f32 * base_delta);
-        let is_bounce = ((new_base < 0.0) && sum.is_positive);
-        let exponent_difference = new_base.log10().floor()
-            - match is_bounce {
-                false => new_base.log10().floor(),
-                true => new_base.log10().floor() - other_min.base.log10(),
+    fn unbounded_div(self, other: BigNumber<T>) -> Unbounded<T::Wide> {
+        // x / ±0 is ±inf and 0 / 0 is NaN, as for f64
+        if (other.is_zero()) {
+            let zero = match other.is_positive {
+                true => T::Wide::zero(),
+                false => T::Wide::neg_zero(),
             };
-        if (is_bounce) {
-            sum.is_positive = false;
-        }
-        sum.increase_power(
-            match exponent_difference as i32 * sign_from_bool(other_min.is_positive) {
-                x => {
-                    let mut new_x = x;
-                    if (is_bounce) {
-                        if (x > 0) {
-                            new_x = x + 1;
-                        } else {
-                            new_x = x - 1;
-                        }
-                    } else {
-                        new_x += other_min.base.log10().floor() as i32;
-                    }
-                    new_x
-                }
+            return Unbounded::new(self.signed_base() / zero, 0);
+        }
+        if let Some(result) = self.non_finite_operation(&other, |a, b| a / b) {
+            return result;
+        }
+        Unbounded::new(
+            self.signed_base() / other.signed_base(),
+            self.exponent as i64 - other.exponent as i64,
+        )
+    }
+    // Follows the sign of the dividend, like the primitive `%`
+    fn unbounded_rem(self, other: BigNumber<T>) -> Unbounded<T::Wide> {
+        if (other.is_zero()) {
+            return Unbounded::new(T::Wide::nan(), 0);
+        }
+        // x % ±inf is x for finite x, anything else involving NaN or inf is NaN
+        if (self.is_finite() && other.is_infinite()) {
+            return Unbounded::from(self);
+        }
+        if let Some(result) = self.non_finite_operation(&other, |a, b| a % b) {
+            return result;
+        }
+        let difference = self.exponent as i64 - other.exponent as i64;
+        if (self.is_zero() || difference < 0) {
+            return Unbounded::from(self);
+        }
+        // Past this point the shifted dividend no longer fits the wide mantissa and has far
+        // more digits than it holds, so whatever is left over is below its precision
+        let limit = T::Wide::max_value().log10().to_f64().unwrap() - 1.0;
+        if (difference as f64 > limit) {
+            return Unbounded::new(T::Wide::zero(), 0);
+        }
+        let ten = from_f64::<T::Wide>(10.0);
+        let remainder = (self.base.widen() * ten.powi(difference as i32)) % other.base.widen();
+        Unbounded::new(
+            match self.is_positive {
+                true => remainder,
+                false => -remainder,
             },
-            is_bounce,
-        );
-        sum.base = get_first_significant_figure(new_base.abs());
-        match sum.serialized {
-            Format::Haven(_) => {
-                sum.serialized = Format::Haven(Haven::create(sum.base, sum.exponent, false));
-            }
-            Format::Scientific(_) => {
-                sum.serialized =
-                    Format::Scientific(Scientific::create(sum.base, sum.exponent, false));
-            }
-        }
-        sum
-    }
-}
-impl ops::Mul<f32> for BigNumber {
-    type Output = BigNumber;
-    fn mul(self, rhs: f32) -> Self::Output {
-        let big_version = BigNumber::new_d(rhs);
-        self * big_version
-    }
-}
-impl ops::Mul for BigNumber {
-    type Output = BigNumber;
-    fn mul(self, other: BigNumber) -> Self::Output {
-        let mut product = self.clone();
-        let multiplier = match product.serialized {
-            Format::Haven(ref x) => Haven::get_multiplier(x.to_string(), product.exponent),
-            Format::Scientific(ref x) => Ok(product.base),
-        }
-        .ok()
-        .unwrap()
-            * match other.serialized {
-                Format::Haven(ref x) => Haven::get_multiplier(x.to_string(), other.exponent),
-                Format::Scientific(ref x) => Ok(other.base),
-            }
-            .ok()
-            .unwrap();
-        let mut new_multiplier: f32 = 0.0;
-        match product.serialized {
-            Format::Haven(ref x) => {
-                let original_multiplier = Haven::get_multiplier(x.to_string(), product.exponent);
-                let unwrapped_multiplier = original_multiplier.ok().unwrap();
-                let difference = multiplier.log10().floor() - unwrapped_multiplier.log10().floor();
-                let factor = (10 as i32).pow(match exponential_modulo_ten(
-                    multiplier,
-                    unwrapped_multiplier,
-                ) {
-                    x => {
-                        let mut result = (unwrapped_multiplier).log10().floor() as i32 + x;
-                        let is_less_than = result < 0;
-                        let is_greater_than = result > 2;
-                        if (is_greater_than) {
-                            result = 0;
-                        } else if (is_less_than) {
-                            result = 2;
-                        };
-                        result
-                    }
-                } as u32);
-                new_multiplier =
-                    get_first_significant_figure(unwrapped_multiplier * other.base) * factor as f32;
-                ()
-            }
-            Format::Scientific(ref x) => {
-                new_multiplier = get_first_significant_figure(multiplier);
-                let change = (get_first_significant_figure(self.base)
-                    * get_first_significant_figure(other.base))
-                .log10()
-                .floor();
-                if (change == 1.0) {
-                    product.increase_power(change as i32, false);
+            other.exponent as i64,
+        )
+    }
+}
+// Like floats, results too large for the exponent become infinite and results too small
+// become zero; see `overflow` for the checked, saturating and wrapping versions
+macro_rules! impl_big_number_operators {
+    ($($trait:ident, $method:ident, $unbounded:ident);*) => {
+        $(
+            impl<T: Mantissa> ops::$trait for BigNumber<T> {
+                type Output = BigNumber<T>;
+                fn $method(self, other: BigNumber<T>) -> BigNumber<T> {
+                    self.$unbounded(other).to_big_number()
                 }
-                ()
             }
-        }
-        product.base = get_first_significant_figure(new_multiplier);
-        product.increase_power(other.exponent, false);
-        match product.serialized {
-            Format::Haven(_) => {
-                product.serialized = Format::Haven(Haven::create(
-                    round_to_order(new_multiplier),
-                    product.exponent,
-                    true,
-                ));
+        )*
+    };
+}
+impl_big_number_operators!(
+    Add, add, unbounded_add;
+    Sub, sub, unbounded_sub;
+    Mul, mul, unbounded_mul;
+    Div, div, unbounded_div;
+    Rem, rem, unbounded_rem
+);
+macro_rules! impl_big_number_operations {
+    ($($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {
+        $(
+            impl<T: Mantissa> ops::$trait<T> for BigNumber<T> {
+                type Output = BigNumber<T>;
+                fn $method(self, rhs: T) -> BigNumber<T> {
+                    ops::$trait::$method(self, BigNumber::new_d(rhs))
+                }
             }
-            Format::Scientific(_) => {
-                product.serialized =
-                    Format::Scientific(Scientific::create(new_multiplier, product.exponent, true));
+            impl ops::$trait<BigNumber<f32>> for f32 {
+                type Output = BigNumber<f32>;
+                fn $method(self, rhs: BigNumber<f32>) -> BigNumber<f32> {
+                    ops::$trait::$method(BigNumber::new_d(self), rhs)
+                }
             }
-        }
-        product.is_positive = bool_from_number(
-            sign_from_bool(product.is_positive) * sign_from_bool(other.is_positive),
-        );
-        product
-    }
-}
-enum AnalysisErrors {
-    // Haven
-    InvalidPrefix,
-    InvalidSuffix,
-    //
-    InvalidExponent,
+            impl ops::$trait<BigNumber<f64>> for f64 {
+                type Output = BigNumber<f64>;
+                fn $method(self, rhs: BigNumber<f64>) -> BigNumber<f64> {
+                    ops::$trait::$method(BigNumber::new_d(self), rhs)
+                }
+            }
+            impl<T: Mantissa> ops::$assign_trait for BigNumber<T> {
+                fn $assign_method(&mut self, rhs: BigNumber<T>) {
+                    *self = ops::$trait::$method(self.clone(), rhs);
+                }
+            }
+            impl<T: Mantissa> ops::$assign_trait<T> for BigNumber<T> {
+                fn $assign_method(&mut self, rhs: T) {
+                    *self = ops::$trait::$method(self.clone(), rhs);
+                }
+            }
+        )*
+    };
 }
+impl_big_number_operations!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
 trait NumberMethods {
-    fn get_exponent(x: String) -> Result<i32, AnalysisErrors>;
-    fn get_multiplier(x: String, exponent: i32) -> Result<f32, AnalysisErrors>;
-    fn create(a: f32, b: i32, is_product: bool) -> String;
+    fn get_exponent(x: String) -> Result<i32, BigNumberError>;
+    // The digits of the normalized mantissa, written as "d.ddd"
+    fn get_mantissa(x: String) -> Result<String, BigNumberError>;
+    fn get_multiplier<T: Mantissa>(x: String) -> Result<T, BigNumberError> {
+        Self::get_mantissa(x.clone())?
+            .parse::<T>()
+            .map_err(|_| BigNumberError::new(ErrorCause::BadPrefix, &x, Some(0)))
+    }
+    fn create<T: Mantissa>(a: T, b: i32, is_product: bool) -> String;
+    fn serialize<T: Mantissa>(a: T, b: i32) -> Format;
 }
 struct Haven;
 struct Scientific;
+struct Engineering;
+struct SI;
+// Moves the decimal point of a plain decimal string to the right without going through a float
+fn shift_decimal_point(number: &str, places: usize) -> String {
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let mut digits = format!("{}{}", integer, fraction);
+    let point = integer.len() + places;
+    while (digits.len() < point) {
+        digits.push('0');
+    }
+    let (new_integer, new_fraction) = digits.split_at(point);
+    match new_fraction.is_empty() {
+        true => new_integer.to_string(),
+        false => format!("{}.{}", new_integer, new_fraction),
+    }
+}
+// Rewrites a plain decimal string as a mantissa in [1, 10) and the power of ten it was
+// moved by, e.g. "0016.5" => ("1.65", 1), so the mantissa parses to exactly the float it was written from
+fn normalize_decimal(number: &str) -> Result<(String, i32), BigNumberError> {
+    let mut has_point = false;
+    for (index, char) in number.char_indices() {
+        match char {
+            '0'..='9' => {}
+            '.' if !has_point => has_point = true,
+            _ => {
+                return Err(BigNumberError::new(
+                    ErrorCause::BadPrefix,
+                    number,
+                    Some(index),
+                ))
+            }
+        }
+    }
+    let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let digits = format!("{}{}", integer, fraction);
+    if (digits.is_empty()) {
+        return Err(BigNumberError::new(ErrorCause::BadPrefix, number, Some(0)));
+    }
+    let leading_zeros = digits.chars().take_while(|char| *char == '0').count();
+    if (leading_zeros == digits.len()) {
+        return Ok(("0".to_string(), 0));
+    }
+    let significant = &digits[leading_zeros..];
+    let mantissa = match significant.len() {
+        1 => significant.to_string(),
+        _ => format!("{}.{}", &significant[..1], &significant[1..]),
+    };
+    Ok((mantissa, integer.len() as i32 - leading_zeros as i32 - 1))
+}
+impl Haven {
+    fn write(mantissa: &str, exponent: i32) -> String {
+        Haven::append_suffix(
+            shift_decimal_point(mantissa, exponent.rem_euclid(3) as usize),
+            exponent,
+        )
+    }
+    fn append_suffix(mut serialized: String, exponent: i32) -> String {
+        let table = active_table();
+        if let Some(x) = table.suffix(exponent).filter(|x| !x.is_empty()) {
+            serialized.push_str(table.separator());
+            serialized.push_str(x);
+        }
+        serialized
+    }
+    // Splits "1.5QD" (or "1.5 milliard") into the number and the exponent its suffix stands for
+    fn split(x: &str) -> Result<(&str, i32), BigNumberError> {
+        if let Some((number, suffix_exponent)) = active_table().split_suffix(x) {
+            return Ok((number.trim_end(), suffix_exponent));
+        }
+        let number = x.trim_end_matches(|char: char| char.is_alphabetic() || char.is_whitespace());
+        let suffix = x[number.len()..].trim_start();
+        match suffix.is_empty() {
+            true => Ok((number, 0)),
+            false => Err(BigNumberError::new(
+                ErrorCause::UnknownSuffix,
+                x,
+                Some(x.len() - suffix.len()),
+            )),
+        }
+    }
+}
 impl NumberMethods for Haven {
-    fn get_exponent(x: String) -> Result<i32, AnalysisErrors> {
-        let mut abbreviation = String::new();
-        let mut rest = x
-            .chars()
-            .rev()
-            .skip_while(|char| {
-                let is_alphabetic = char.is_ascii_alphabetic();
-                if (is_alphabetic) {
-                    abbreviation.push(*char);
+    fn get_exponent(x: String) -> Result<i32, BigNumberError> {
+        let (number, suffix_exponent) = Haven::split(&x)?;
+        let (_, shift) = normalize_decimal(number)?;
+        Ok(suffix_exponent + shift)
+    }
+    fn get_mantissa(x: String) -> Result<String, BigNumberError> {
+        let (number, _) = Haven::split(&x)?;
+        let (mantissa, _) = normalize_decimal(number)?;
+        Ok(mantissa)
+    }
+    fn create<T: Mantissa>(a: T, b: i32, is_product: bool) -> String {
+        if (is_product) {
+            return Haven::append_suffix(a.to_string(), b);
+        }
+        Haven::write(&get_first_significant_figure(a).to_string(), b)
+    }
+    fn serialize<T: Mantissa>(a: T, b: i32) -> Format {
+        serialize_parts(a, b)
+    }
+}
+impl Scientific {
+    fn write(mantissa: &str, exponent: i32) -> String {
+        format!("{}x10^{}", mantissa, exponent)
+    }
+    // Splits both "3.2x10^-5" and "3.2e-5" into the mantissa and the written exponent
+    fn split(x: &str) -> Result<(&str, &str), BigNumberError> {
+        match x.split_once("x10^") {
+            Some(parts) => Ok(parts),
+            // Only "1.5e3"-style numbers, so an "e" inside a Haven suffix is not taken for one
+            None => x
+                .split_once(['e', 'E'])
+                .filter(|(number, _)| {
+                    number
+                        .chars()
+                        .all(|char| char.is_ascii_digit() || char == '.')
+                })
+                .ok_or_else(|| BigNumberError::new(ErrorCause::BadExponent, x, Some(x.len()))),
+        }
+    }
+}
+impl NumberMethods for Scientific {
+    fn get_exponent(x: String) -> Result<i32, BigNumberError> {
+        let (number, exponent) = Scientific::split(&x)?;
+        let (_, shift) = normalize_decimal(number)?;
+        let start = x.len() - exponent.len();
+        let written = exponent.parse::<i32>().map_err(|error| {
+            let cause = match error.kind() {
+                IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+                    ErrorCause::ExponentOverflow
                 }
-                is_alphabetic
-            })
-            .collect::<String>();
-        abbreviation = abbreviation.chars().rev().collect::<String>();
-        rest = rest.chars().rev().collect::<String>();
-        if (abbreviation.is_empty()) {
-            let num = x.parse::<f32>();
-            let unwrapped = match num {
-                Ok(n) => n,
-                Err(error) => -1.0,
+                _ => ErrorCause::BadExponent,
             };
-            if (!bool_from_number(unwrapped as i32)) {
-                return Err(AnalysisErrors::InvalidPrefix);
-            }
-            return Ok(((unwrapped as f32).log10().floor()) as i32);
-        } else {
-            let mut position = None;
-            for (index, suffix) in HAVEN_ABBREVIATIONS.iter().enumerate() {
-                if (index == 0) {
-                    continue;
+            BigNumberError::new(cause, &x, Some(start))
+        })?;
+        written
+            .checked_add(shift)
+            .ok_or_else(|| BigNumberError::new(ErrorCause::ExponentOverflow, &x, Some(start)))
+    }
+    fn get_mantissa(x: String) -> Result<String, BigNumberError> {
+        let (number, _) = Scientific::split(&x)?;
+        let (mantissa, _) = normalize_decimal(number)?;
+        Ok(mantissa)
+    }
+    fn create<T: Mantissa>(a: T, b: i32, _is_product: bool) -> String {
+        Scientific::write(&get_first_significant_figure(a).to_string(), b)
+    }
+    fn serialize<T: Mantissa>(a: T, b: i32) -> Format {
+        match a.is_zero() {
+            true => serialize_parts(a, b),
+            false => Format::Scientific(Scientific::create(a, b, false)),
+        }
+    }
+}
+impl Engineering {
+    fn write(mantissa: &str, exponent: i32) -> String {
+        let scale = engineering_exponent(exponent);
+        Scientific::write(
+            &shift_decimal_point(mantissa, (exponent - scale) as usize),
+            scale,
+        )
+    }
+}
+impl NumberMethods for Engineering {
+    fn get_exponent(x: String) -> Result<i32, BigNumberError> {
+        Scientific::get_exponent(x)
+    }
+    fn get_mantissa(x: String) -> Result<String, BigNumberError> {
+        Scientific::get_mantissa(x)
+    }
+    fn create<T: Mantissa>(a: T, b: i32, is_product: bool) -> String {
+        if (is_product) {
+            return Scientific::write(&a.to_string(), engineering_exponent(b));
+        }
+        Engineering::write(&get_first_significant_figure(a).to_string(), b)
+    }
+    fn serialize<T: Mantissa>(a: T, b: i32) -> Format {
+        match a.is_zero() {
+            true => serialize_parts(a, b),
+            false => Format::Engineering(Engineering::create(a, b, false)),
+        }
+    }
+}
+impl SI {
+    // Written as "149.6 G" for the unit to follow; past the prefixes the power of ten is
+    // written out instead, "1.5x10^33 "
+    fn write(mantissa: &str, exponent: i32) -> String {
+        let index = (exponent as i64 - SI_PREFIX_OFFSET as i64).div_euclid(3);
+        match SI_PREFIXES.get(index as usize).filter(|_| index >= 0) {
+            Some(prefix) => format!(
+                "{} {}",
+                shift_decimal_point(mantissa, exponent.rem_euclid(3) as usize),
+                prefix
+            ),
+            None => format!("{} ", Engineering::write(mantissa, exponent)),
+        }
+    }
+    fn prefix_exponent(prefix: char) -> Option<i32> {
+        let prefix = match prefix {
+            'u' => 'µ',
+            _ => prefix,
+        };
+        SI_PREFIXES
+            .iter()
+            .position(|candidate| candidate.starts_with(prefix))
+            .map(|position| position as i32 * 3 + SI_PREFIX_OFFSET)
+    }
+    // Splits "149.6 Gm" into the number, the exponent of the prefix and the unit. After a
+    // space a lone letter is the unit ("5 m"); right after the number it is a prefix ("5m")
+    fn split(x: &str) -> (&str, i32, &str) {
+        let (number, word) = x.split_at(x.trim_end_matches(char::is_alphabetic).len());
+        let mut chars = word.chars();
+        let prefixed = match chars.next() {
+            Some(first)
+                if (!number.ends_with(char::is_whitespace) || chars.clone().next().is_some()) =>
+            {
+                SI::prefix_exponent(first).map(|exponent| (exponent, chars.as_str()))
+            }
+            _ => None,
+        };
+        let (exponent, unit) = prefixed.unwrap_or((0, word));
+        (number.trim_end(), exponent, unit)
+    }
+    fn is_spaced(x: &str) -> bool {
+        x.trim_end_matches(char::is_alphabetic)
+            .ends_with(char::is_whitespace)
+    }
+}
+impl NumberMethods for SI {
+    fn get_exponent(x: String) -> Result<i32, BigNumberError> {
+        let (number, prefix_exponent, _) = SI::split(&x);
+        let exponent = match is_scientific(number) {
+            true => Scientific::get_exponent(number.to_string())?,
+            false => normalize_decimal(number)?.1,
+        };
+        exponent
+            .checked_add(prefix_exponent)
+            .ok_or_else(|| BigNumberError::new(ErrorCause::ExponentOverflow, &x, None))
+    }
+    fn get_mantissa(x: String) -> Result<String, BigNumberError> {
+        let (number, _, _) = SI::split(&x);
+        match is_scientific(number) {
+            true => Scientific::get_mantissa(number.to_string()),
+            false => Ok(normalize_decimal(number)?.0),
+        }
+    }
+    fn create<T: Mantissa>(a: T, b: i32, is_product: bool) -> String {
+        if (is_product) {
+            let mut written = SI::write("1", engineering_exponent(b));
+            written.replace_range(..1, &a.to_string());
+            return written;
+        }
+        SI::write(&get_first_significant_figure(a).to_string(), b)
+    }
+    fn serialize<T: Mantissa>(a: T, b: i32) -> Format {
+        match a.is_zero() {
+            true => serialize_parts(a, b),
+            false => Format::SI(SI::create(a, b, false)),
+        }
+    }
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BigVec2<T: Mantissa = f32> {
+    pub x: BigNumber<T>,
+    pub y: BigNumber<T>,
+}
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BigVec3<T: Mantissa = f32> {
+    pub x: BigNumber<T>,
+    pub y: BigNumber<T>,
+    pub z: BigNumber<T>,
+}
+// atan2 of two BigNumbers, brought to a shared exponent first
+fn angle_of<T: Mantissa>(y: &BigNumber<T>, x: &BigNumber<T>) -> T {
+    let exponent = y.exponent.max(x.exponent);
+    let scaled = |number: &BigNumber<T>| {
+        let base = number.signed_base().to_f64().unwrap();
+        base * 10.0_f64.powi(number.exponent.saturating_sub(exponent).max(-400))
+    };
+    from_f64(scaled(y).atan2(scaled(x)))
+}
+// Everything BigVec2 and BigVec3 share, done component by component
+macro_rules! impl_big_vector {
+    ($vector:ident { $($component:ident),* }) => {
+        impl<T: Mantissa> $vector<T> {
+            pub fn new($($component: BigNumber<T>),*) -> $vector<T> {
+                $vector { $($component),* }
+            }
+            pub fn zero() -> $vector<T> {
+                $vector { $($component: BigNumber::new_d(T::zero())),* }
+            }
+            pub fn cast<U: Mantissa>(&self) -> $vector<U> {
+                $vector { $($component: self.$component.cast()),* }
+            }
+            fn map(self, f: impl Fn(BigNumber<T>) -> BigNumber<T>) -> $vector<T> {
+                $vector { $($component: f(self.$component)),* }
+            }
+            fn zip(
+                self,
+                other: $vector<T>,
+                f: impl Fn(BigNumber<T>, BigNumber<T>) -> BigNumber<T>,
+            ) -> $vector<T> {
+                $vector { $($component: f(self.$component, other.$component)),* }
+            }
+            pub fn scale(&self, factor: BigNumber<T>) -> $vector<T> {
+                self.clone() * factor
+            }
+            pub fn dot(&self, other: &$vector<T>) -> BigNumber<T> {
+                let mut sum = BigNumber::new_d(T::zero());
+                $(sum += self.$component.clone() * other.$component.clone();)*
+                sum
+            }
+            pub fn length_squared(&self) -> BigNumber<T> {
+                self.dot(self)
+            }
+            pub fn length(&self) -> BigNumber<T> {
+                self.length_squared().sqrt()
+            }
+            // The zero vector gives NaN components, like 0 / 0
+            pub fn normalize(&self) -> $vector<T> {
+                self.clone() / self.length()
+            }
+            pub fn distance(&self, other: &$vector<T>) -> BigNumber<T> {
+                (other.clone() - self.clone()).length()
+            }
+            pub fn lerp(&self, other: &$vector<T>, t: T) -> $vector<T> {
+                self.clone() + (other.clone() - self.clone()) * t
+            }
+        }
+        impl From<$vector<f32>> for $vector<f64> {
+            fn from(vector: $vector<f32>) -> $vector<f64> {
+                vector.cast()
+            }
+        }
+        impl<T: Mantissa> ops::Neg for $vector<T> {
+            type Output = $vector<T>;
+            fn neg(self) -> $vector<T> {
+                self.map(|component| -component)
+            }
+        }
+        impl<T: Mantissa> ops::Add for $vector<T> {
+            type Output = $vector<T>;
+            fn add(self, other: $vector<T>) -> $vector<T> {
+                self.zip(other, |a, b| a + b)
+            }
+        }
+        impl<T: Mantissa> ops::Sub for $vector<T> {
+            type Output = $vector<T>;
+            fn sub(self, other: $vector<T>) -> $vector<T> {
+                self.zip(other, |a, b| a - b)
+            }
+        }
+        impl<T: Mantissa> ops::AddAssign for $vector<T> {
+            fn add_assign(&mut self, other: $vector<T>) {
+                *self = self.clone() + other;
+            }
+        }
+        impl<T: Mantissa> ops::SubAssign for $vector<T> {
+            fn sub_assign(&mut self, other: $vector<T>) {
+                *self = self.clone() - other;
+            }
+        }
+    };
+}
+impl_big_vector!(BigVec2 { x, y });
+impl_big_vector!(BigVec3 { x, y, z });
+impl<T: Mantissa> BigVec2<T> {
+    // The z component of the 3D cross product
+    pub fn cross(&self, other: &BigVec2<T>) -> BigNumber<T> {
+        self.x * other.y - self.y * other.x
+    }
+    // Direction in radians from the positive x axis
+    pub fn angle(&self) -> T {
+        angle_of(&self.y, &self.x)
+    }
+    // Unsigned angle to `other` in radians
+    pub fn angle_between(&self, other: &BigVec2<T>) -> T {
+        angle_of(&self.cross(other), &self.dot(other)).abs()
+    }
+    // Screen coordinates are `origin + self / scale`
+    pub fn to_vec2(self, scale: &BigNumber<T>, origin: Vec2) -> Vec2 {
+        let scaled = self / *scale;
+        origin
+            + vec2(
+                scaled.x.to_float().to_f32().unwrap(),
+                scaled.y.to_float().to_f32().unwrap(),
+            )
+    }
+    pub fn from_vec2(vector: Vec2, scale: &BigNumber<T>, origin: Vec2) -> BigVec2<T> {
+        let offset = vector - origin;
+        BigVec2 {
+            x: BigNumber::new_d(from_f64(offset.x as f64)),
+            y: BigNumber::new_d(from_f64(offset.y as f64)),
+        } * *scale
+    }
+}
+impl<T: Mantissa> BigVec3<T> {
+    pub fn cross(&self, other: &BigVec3<T>) -> BigVec3<T> {
+        BigVec3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+    pub fn angle_between(&self, other: &BigVec3<T>) -> T {
+        angle_of(&self.cross(other).length(), &self.dot(other))
+    }
+    pub fn to_vec3(self, scale: &BigNumber<T>, origin: Vec3) -> Vec3 {
+        let scaled = self / *scale;
+        origin
+            + vec3(
+                scaled.x.to_float().to_f32().unwrap(),
+                scaled.y.to_float().to_f32().unwrap(),
+                scaled.z.to_float().to_f32().unwrap(),
+            )
+    }
+    pub fn from_vec3(vector: Vec3, scale: &BigNumber<T>, origin: Vec3) -> BigVec3<T> {
+        let offset = vector - origin;
+        BigVec3 {
+            x: BigNumber::new_d(from_f64(offset.x as f64)),
+            y: BigNumber::new_d(from_f64(offset.y as f64)),
+            z: BigNumber::new_d(from_f64(offset.z as f64)),
+        } * *scale
+    }
+}
+// Scalars are applied to every component
+macro_rules! impl_big_vector_scalar_operations {
+    ($vector:ident; $($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {
+        $(
+            impl<T: Mantissa> ops::$trait<BigNumber<T>> for $vector<T> {
+                type Output = $vector<T>;
+                fn $method(self, rhs: BigNumber<T>) -> $vector<T> {
+                    self.map(|component| ops::$trait::$method(component, rhs.clone()))
+                }
+            }
+            impl<T: Mantissa> ops::$trait<T> for $vector<T> {
+                type Output = $vector<T>;
+                fn $method(self, rhs: T) -> $vector<T> {
+                    ops::$trait::$method(self, BigNumber::new_d(rhs))
                 }
-                if (*suffix.unwrap() == abbreviation) {
-                    position = Some(index);
-                    break;
+            }
+            impl<T: Mantissa> ops::$assign_trait<BigNumber<T>> for $vector<T> {
+                fn $assign_method(&mut self, rhs: BigNumber<T>) {
+                    *self = ops::$trait::$method(self.clone(), rhs);
                 }
             }
-            if (position.is_some()) {
-                let parsed_rest = rest.parse::<f32>();
-                if (parsed_rest.is_err()) {
-                    return Err(AnalysisErrors::InvalidPrefix);
+            impl<T: Mantissa> ops::$assign_trait<T> for $vector<T> {
+                fn $assign_method(&mut self, rhs: T) {
+                    *self = ops::$trait::$method(self.clone(), rhs);
                 }
-                let result = parsed_rest.ok().unwrap().log10().floor();
-                return Ok(((position.unwrap() as i32 * 3 as i32)
-                    - reverse_number(1, 3, result as i32)
-                    + 1));
-            } else {
-                return Err(AnalysisErrors::InvalidSuffix);
             }
+        )*
+    };
+}
+impl_big_vector_scalar_operations!(
+    BigVec2;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+impl_big_vector_scalar_operations!(
+    BigVec3;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign;
+    Rem, rem, RemAssign, rem_assign
+);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use format_policy::FormatPolicy;
+    // (written form, base, exponent, is_positive)
+    const PARSE_CORPUS: [(&str, f32, i32, bool); 16] = [
+        ("0.0", 0.0, 0, true),
+        ("7", 7.0, 0, true),
+        ("-7", 7.0, 0, false),
+        ("15K", 1.5, 4, true),
+        ("150B", 1.5, 11, true),
+        ("4.2QD", 4.2, 15, true),
+        ("-999.5SP", 9.995, 26, false),
+        ("0.25", 2.5, -1, true),
+        ("0016.5", 1.65, 1, true),
+        ("1.3x10^27", 1.3, 27, true),
+        ("3.2x10^-5", 3.2, -5, true),
+        ("-3.2x10^-5", 3.2, -5, false),
+        ("+6.02e23", 6.02, 23, true),
+        ("6.02E+23", 6.02, 23, true),
+        ("12.5x10^3", 1.25, 4, true),
+        ("0.5e-3", 5.0, -4, true),
+    ];
+    #[test]
+    fn parses_corpus() {
+        for (written, base, exponent, is_positive) in PARSE_CORPUS {
+            let parsed = written.parse::<BigNumber>().unwrap();
+            assert_eq!(parsed.base, base, "{}", written);
+            assert_eq!(parsed.exponent, exponent, "{}", written);
+            assert_eq!(parsed.is_positive, is_positive, "{}", written);
         }
     }
-    fn get_multiplier(x: String, exponent: i32) -> Result<f32, AnalysisErrors> {
-        match x
-            .chars()
-            .take_while(|char| char.is_ascii_digit() || *char == '.')
-            .collect::<String>()
-            .parse::<f32>()
-        {
-            Ok(number) => match (number < 1000.0) {
-                true => Ok(number),
-                false => Ok(get_first_significant_figure(number)
-                    * (cyclic_wrap((((number).log10()).floor() as i32), exponent, 3)) as f32),
-            },
-            Err(error) => Err(AnalysisErrors::InvalidPrefix),
+    #[test]
+    fn rejects_malformed_input() {
+        for (written, cause, offset) in [
+            ("", ErrorCause::BadPrefix, 0),
+            ("K", ErrorCause::BadPrefix, 0),
+            ("1.2.3K", ErrorCause::BadPrefix, 3),
+            ("  -4.2QQ", ErrorCause::UnknownSuffix, 6),
+            ("1.5x10^", ErrorCause::BadExponent, 7),
+            ("1.5x10^4.5", ErrorCause::BadExponent, 7),
+            ("-1.5e99999999999", ErrorCause::ExponentOverflow, 5),
+            ("99x10^2147483647", ErrorCause::ExponentOverflow, 6),
+        ] {
+            let error = written.parse::<BigNumber>().unwrap_err();
+            assert_eq!(error.cause, cause, "{}", written);
+            assert_eq!(error.offset, Some(offset), "{}", written);
+            assert_eq!(error.input, written);
         }
     }
-    fn create(a: f32, b: i32, is_product: bool) -> String {
-        let mut serialized = String::new();
-        let abbreviation = HAVEN_ABBREVIATIONS[(b as f32 / 3.0).floor() as usize];
-        if (is_product) {
-            serialized.push_str(a.to_string().as_str());
-        } else {
-            serialized.push_str(
-                (get_first_significant_figure(a) * Real::powi(10.0, b % 3))
-                    .to_string()
-                    .as_str(),
-            );
+    #[test]
+    fn power_changes_report_overflow() {
+        let mut number = "1.5x10^2147483640".parse::<BigNumber>().unwrap();
+        let error = number.increase_power(10).unwrap_err();
+        assert_eq!(error.cause, ErrorCause::ExponentOverflow);
+        assert_eq!(error.input, "1.5x10^2147483640");
+        assert_eq!(number.exponent, 2147483640);
+        assert!(number.decrease_power(i32::MIN).is_err());
+        assert!(number.increase_power(7).is_ok());
+        assert_eq!(number.exponent, i32::MAX);
+    }
+    #[test]
+    fn round_trips_created_values() {
+        let bases = [1.0, 1.5, 2.25, std::f32::consts::PI, 6.6743, 9.999999];
+        for base in bases {
+            for exponent in -40..=40 {
+                for is_positive in [true, false] {
+                    let number = BigNumber {
+                        base,
+                        exponent,
+                        is_positive,
+                    };
+                    for policy in [
+                        FormatPolicy::default(),
+                        FormatPolicy::new(Notation::Haven),
+                        FormatPolicy::new(Notation::Scientific),
+                    ] {
+                        let written = policy.format(&number);
+                        let parsed = written.parse::<BigNumber>().unwrap();
+                        assert_eq!(parsed, number, "{}", written);
+                        assert_eq!(policy.format(&parsed), written);
+                    }
+                }
+            }
         }
-        match abbreviation {
-            Some(x) => {
-                serialized.push_str(abbreviation.unwrap());
+    }
+    #[test]
+    fn round_trips_arithmetic_results() {
+        let mut number = BigNumber::new_d(1.0);
+        for step in 0..200 {
+            number = number * 3.7 - 1.25;
+            if (step % 7 == 0) {
+                number = -number;
             }
-            _ => {}
+            let parsed = number.to_string().parse::<BigNumber>().unwrap();
+            assert_eq!(parsed, number, "{}", number);
         }
-        serialized
     }
-}
-impl NumberMethods for Scientific {
-    fn get_exponent(x: String) -> Result<i32, AnalysisErrors> {
-        let mut exponent = x
-            .chars()
-            .rev()
-            .take_while(|char| char.is_ascii_digit())
-            .collect::<String>();
-        exponent = exponent.chars().rev().collect::<String>();
-        let parsed_exponent = exponent.parse::<i32>();
-        match parsed_exponent {
-            Ok(number) => Ok(number),
-            Err(error) => Err(AnalysisErrors::InvalidExponent),
-        }
-    }
-    fn get_multiplier(x: String, exponent: i32) -> Result<f32, AnalysisErrors> {
-        Haven::get_multiplier(x, exponent)
-    }
-    fn create(a: f32, b: i32, is_product: bool) -> String {
-        let mut serialized = String::new();
-        serialized.push_str(get_first_significant_figure(a).to_string().as_str());
-        serialized.push_str("x10^");
-        serialized.push_str(b.to_string().as_str());
-        serialized
+    #[test]
+    fn new_reads_the_given_string() {
+        let haven: BigNumber = BigNumber::new(Format::Haven("-4.2QD".to_string())).unwrap();
+        assert_eq!(haven.to_string(), "-4.2QD");
+        let scientific: BigNumber =
+            BigNumber::new(Format::Scientific("1.5x10^4".to_string())).unwrap();
+        assert_eq!(scientific, "15K".parse::<BigNumber>().unwrap());
+        assert_eq!(scientific.to_string(), "15K");
+    }
+    #[test]
+    fn f64_mantissa_keeps_more_digits() {
+        let third = BigNumber::new_d(1.0_f64) / 3.0;
+        assert_eq!(third.to_string(), "3.333333333333333x10^-1");
+        let narrow: BigNumber = third.cast();
+        assert_eq!(narrow.to_string(), "3.3333333x10^-1");
+        let widened: BigNumber<f64> = "1.1QD".parse::<BigNumber>().unwrap().into();
+        assert_eq!(widened.base, 1.1);
+        assert_eq!(widened.exponent, 15);
+        let rounded_up: BigNumber = "9.999999999x10^3".parse::<BigNumber<f64>>().unwrap().cast();
+        assert_eq!(rounded_up.to_string(), "10K");
+    }
+    #[test]
+    fn writes_engineering_and_si() {
+        let distance = "1.496x10^11".parse::<BigNumber>().unwrap();
+        let engineering = distance.to_format(&Notation::Engineering);
+        assert_eq!(engineering.to_string(), "149.6x10^9");
+        let si = distance.to_format(&Notation::SI("m".to_string()));
+        assert_eq!(si.to_string(), "149.6 Gm");
+        assert_eq!(BigNumber::<f32>::new(engineering).unwrap(), distance);
+        assert_eq!(BigNumber::<f32>::new(si).unwrap(), distance);
+        let si = |unit: &str| Notation::SI(unit.to_string());
+        for (written, exponent, notation) in [
+            ("149.6x10^6", 8, Notation::Engineering),
+            ("149.6 Gm", 11, si("m")),
+            ("5.97 Yg", 24, si("g")),
+            ("-12.5 µs", -5, si("s")),
+            ("12.5 us", -5, si("s")),
+            ("3 m", 0, si("m")),
+            ("1.5x10^33 m", 33, si("m")),
+            ("4.2 kg", 3, si("g")),
+        ] {
+            let number = written.parse::<BigNumber>().unwrap();
+            assert_eq!(number.exponent, exponent, "{}", written);
+            if (!written.contains(" us")) {
+                assert_eq!(FormatPolicy::new(notation).format(&number), written);
+            }
+        }
+        let milli = BigNumber::<f32>::new(Format::SI("3m".to_string())).unwrap();
+        assert_eq!(milli.exponent, -3);
+        let huge = "1.5x10^33".parse::<BigNumber>().unwrap();
+        assert_eq!(
+            huge.to_format(&Notation::SI("g".to_string())).to_string(),
+            "1.5x10^33 g"
+        );
+        let tiny = "-2.5x10^-8".parse::<BigNumber>().unwrap();
+        assert_eq!(
+            tiny.display_with(&FormatPolicy::new(si("m"))).to_string(),
+            "-25 nm"
+        );
+        assert_eq!(Engineering::create(149.6_f32, 11, true), "149.6x10^9");
+        assert_eq!(SI::create(149.6_f32, 11, true), "149.6 G");
+        assert_eq!(
+            SI::get_multiplier::<f32>("149.6 Gm".to_string()).unwrap(),
+            1.496
+        );
+    }
+    #[test]
+    fn vectors_follow_euclidean_algebra() {
+        let number = |x: f64| BigNumber::<f64>::new_d(x);
+        let a = BigVec2::new(number(3.0), number(4.0));
+        let b = BigVec2::new(number(-4.0), number(3.0));
+        assert_eq!(a.length(), number(5.0));
+        assert_eq!(a.dot(&b), number(0.0));
+        assert_eq!(a.cross(&b), number(25.0));
+        assert!((a.angle_between(&b) - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        assert_eq!(b.angle(), 3.0_f64.atan2(-4.0));
+        assert_eq!(a.distance(&b), number(50.0).sqrt());
+        assert_eq!(a.lerp(&b, 0.5), BigVec2::new(number(-0.5), number(3.5)));
+        assert!((a.normalize().length().to_float() - 1.0).abs() < 1e-15);
+        let x = BigVec3::new(number(1.0), number(0.0), number(0.0));
+        let y = BigVec3::new(number(0.0), number(1.0), number(0.0));
+        assert_eq!(
+            x.cross(&y),
+            BigVec3::new(number(0.0), number(0.0), number(1.0))
+        );
+        assert!((x.angle_between(&y) - std::f64::consts::FRAC_PI_2).abs() < 1e-15);
+        // One light year apart, far outside what f32 screen math could hold
+        let far: BigVec3<f64> =
+            BigVec3::new("9.4607x10^15".parse().unwrap(), number(0.0), number(0.0)) * 1.0e300;
+        assert_eq!(far.length().to_string(), "9.4607x10^315");
+    }
+    #[test]
+    fn converts_screen_vectors_with_scale_and_origin() {
+        let scale = "1M".parse::<BigNumber>().unwrap();
+        let origin = vec2(100.0, 50.0);
+        let world = BigVec2::from_vec2(vec2(103.0, 46.0), &scale, origin);
+        assert_eq!(
+            world,
+            BigVec2::new("3M".parse().unwrap(), "-4M".parse().unwrap())
+        );
+        assert_eq!(world.to_vec2(&scale, origin), vec2(103.0, 46.0));
+        let world = BigVec3::from_vec3(vec3(1.0, 2.0, 3.0), &scale, Vec3::ZERO);
+        assert_eq!(world.to_vec3(&scale, Vec3::ZERO), vec3(1.0, 2.0, 3.0));
+    }
+    #[test]
+    fn orders_by_sign_and_magnitude() {
+        let mut numbers: Vec<BigNumber> = [
+            "NaN",
+            "3",
+            "-5e10",
+            "inf",
+            "0",
+            "-inf",
+            "1.5QD",
+            "-2.5x10^-3",
+            "2.9",
+            "-5e9",
+        ]
+        .iter()
+        .map(|written| written.parse().unwrap())
+        .collect();
+        numbers.sort();
+        let sorted: Vec<String> = numbers.iter().map(|number| number.to_string()).collect();
+        assert_eq!(
+            sorted,
+            [
+                "-inf",
+                "-50B",
+                "-5B",
+                "-2.5x10^-3",
+                "0.0",
+                "2.9",
+                "3",
+                "1.5QD",
+                "inf",
+                "NaN"
+            ]
+        );
+        assert!("-5e10".parse::<BigNumber>().unwrap() < BigNumber::new_d(3.0));
+        assert_eq!(BigNumber::<f32>::default(), BigNumber::new_d(0.0));
+        assert_eq!(-BigNumber::new_d(0.0_f32), BigNumber::new_d(0.0));
+        assert_eq!(BigNumber::<f32>::nan(), BigNumber::nan());
+    }
+    #[test]
+    fn hashes_consistently_with_eq() {
+        use std::collections::HashMap;
+        let mut masses = HashMap::new();
+        masses.insert("6x10^24".parse::<BigNumber>().unwrap(), "Earth");
+        masses.insert(BigNumber::nan(), "unknown");
+        masses.insert(BigNumber::new_d(0.0), "massless");
+        let hand_built = BigNumber {
+            base: 60.0_f32,
+            exponent: 23,
+            is_positive: true,
+        }
+        .normalize();
+        assert_eq!(masses.get(&hand_built), Some(&"Earth"));
+        assert_eq!(masses.get(&(BigNumber::nan() * 2.0)), Some(&"unknown"));
+        assert_eq!(masses.get(&-BigNumber::new_d(0.0)), Some(&"massless"));
+        let negative_zero = BigNumber {
+            base: 0.0_f32,
+            exponent: 12,
+            is_positive: false,
+        };
+        assert_eq!(negative_zero.normalize().exponent, 0);
+        assert_eq!(masses.get(&negative_zero), Some(&"massless"));
+        let hash = |number: BigNumber| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            number.hash(&mut hasher);
+            hasher.finish()
+        };
+        let signed_zero = BigNumber {
+            base: -0.0_f32,
+            exponent: 0,
+            is_positive: false,
+        };
+        assert_eq!(signed_zero, BigNumber::new_d(0.0));
+        assert_eq!(hash(signed_zero), hash(BigNumber::new_d(0.0)));
+        assert_eq!(hash(negative_zero), hash(BigNumber::new_d(0.0)));
+    }
+    #[test]
+    fn follows_ieee_for_nan_and_infinity() {
+        let inf = BigNumber::<f32>::infinity();
+        let three = BigNumber::new_d(3.0_f32);
+        assert_eq!(inf + three, inf);
+        assert!((inf - inf).is_nan());
+        assert!((inf * 0.0).is_nan());
+        assert_eq!(three / inf, BigNumber::new_d(0.0));
+        assert_eq!(three % inf, three);
+        let zero = BigNumber::new_d(0.0_f32);
+        assert_eq!(three / zero, inf);
+        assert_eq!(-three / zero, -inf);
+        assert!((zero / zero).is_nan());
+        assert!((three % zero).is_nan());
+        assert_eq!(
+            three.checked_div(zero).unwrap_err().cause,
+            ErrorCause::DivisionByZero
+        );
+        assert_eq!(-inf, BigNumber::neg_infinity());
+        assert_eq!((-inf).to_string(), "-inf");
+        assert_eq!(BigNumber::new_d(f32::NAN).to_string(), "NaN");
+        assert_eq!(BigNumber::new_d(f32::INFINITY), inf);
+        assert_eq!("-Infinity".parse::<BigNumber>().unwrap(), -inf);
+        let mut shifted = inf;
+        shifted.increase_power(5).unwrap();
+        assert_eq!(shifted.exponent, 0);
     }
-}
-#[derive(Debug, Clone)]
-pub struct BigVec2 {
-    pub x: BigNumber,
-    pub y: BigNumber,
 }
@@ -0,0 +1,848 @@
+use super::{BigNumberError, ErrorCause, Mantissa};
+use num::{
+    bigint::BigUint,
+    traits::{Float, Num, One, ToPrimitive, Zero},
+    Integer,
+};
+use std::{cmp::Ordering, fmt, num::FpCategory, ops, str::FromStr};
+// A decimal float with DIGITS significant digits, for a `BigNumber<Decimal>` that is added to
+// over and over without drifting: decimal fractions like 0.1 are exact, and a result is only
+// rounded, half to even, once it needs more than DIGITS digits. Arithmetic, roots and integer
+// powers work on the digits; the other functions go through f64. The default of 34 digits is
+// what IEEE decimal128 keeps, and up to 38 fit the u128 the digits are kept in.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Decimal<const DIGITS: u32 = 34> {
+    // The value is significand x 10^exponent, with no trailing zeros in the significand
+    significand: u128,
+    exponent: i32,
+    // Kept for zero too, like the sign of an f64 zero
+    is_negative: bool,
+    kind: Kind,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Kind {
+    #[default]
+    Finite,
+    Infinite,
+    NaN,
+}
+fn power_of_ten(exponent: u32) -> BigUint {
+    BigUint::from(10_u32).pow(exponent)
+}
+// Decimal digits in `number`, 1 for zero
+fn digit_count(number: &BigUint) -> u32 {
+    match number.to_u128() {
+        Some(small) => small.checked_ilog10().unwrap_or(0) + 1,
+        None => number.to_string().len() as u32,
+    }
+}
+impl<const DIGITS: u32> Decimal<DIGITS> {
+    fn special(kind: Kind, is_negative: bool) -> Decimal<DIGITS> {
+        Decimal {
+            significand: 0,
+            exponent: 0,
+            is_negative,
+            kind,
+        }
+    }
+    fn signed_zero(is_negative: bool) -> Decimal<DIGITS> {
+        Decimal::special(Kind::Finite, is_negative)
+    }
+    // significand x 10^exponent rounded half to even to DIGITS digits
+    fn round(is_negative: bool, significand: BigUint, exponent: i64) -> Decimal<DIGITS> {
+        const {
+            assert!(
+                DIGITS >= 16 && DIGITS <= 38,
+                "a Decimal keeps 16 to 38 digits"
+            )
+        };
+        let place = exponent + digit_count(&significand) as i64 - DIGITS as i64;
+        Decimal::round_at(is_negative, significand, exponent, place)
+    }
+    // significand x 10^exponent rounded half to even to a multiple of 10^place
+    fn round_at(
+        is_negative: bool,
+        significand: BigUint,
+        exponent: i64,
+        place: i64,
+    ) -> Decimal<DIGITS> {
+        if (place <= exponent) {
+            return Decimal::from_digits(is_negative, significand, exponent);
+        }
+        let dropped = place - exponent;
+        // Every digit goes, and they are below half of 10^place
+        if (dropped > digit_count(&significand) as i64) {
+            return Decimal::from_digits(is_negative, BigUint::zero(), place);
+        }
+        let divisor = power_of_ten(dropped as u32);
+        let (quotient, remainder) = significand.div_rem(&divisor);
+        let twice = remainder * 2_u32;
+        let round_up = twice > divisor || (twice == divisor && quotient.is_odd());
+        Decimal::from_digits(is_negative, quotient + round_up as u32, place)
+    }
+    // Takes an already rounded significand: drops its trailing zeros and checks the exponent
+    fn from_digits(is_negative: bool, significand: BigUint, exponent: i64) -> Decimal<DIGITS> {
+        let mut significand = significand.to_u128().unwrap();
+        if (significand == 0) {
+            return Decimal::signed_zero(is_negative);
+        }
+        let mut exponent = exponent;
+        while (significand.is_multiple_of(10)) {
+            significand /= 10;
+            exponent += 1;
+        }
+        if (exponent + significand.ilog10() as i64 > i32::MAX as i64) {
+            return Decimal::special(Kind::Infinite, is_negative);
+        }
+        // Below the smallest exponent the last digits go, down to zero
+        if (exponent < i32::MIN as i64) {
+            return Decimal::round_at(
+                is_negative,
+                BigUint::from(significand),
+                exponent,
+                i32::MIN as i64,
+            );
+        }
+        Decimal {
+            significand,
+            exponent: exponent as i32,
+            is_negative,
+            kind: Kind::Finite,
+        }
+    }
+    fn digits(&self) -> BigUint {
+        BigUint::from(self.significand)
+    }
+    // The power of ten of the leading digit, for finite non-zero values
+    fn order(&self) -> i64 {
+        self.exponent as i64 + self.significand.ilog10() as i64
+    }
+    // What f64 gives for NaN and the infinities: finite operands only matter through their
+    // sign and whether they are zero, so they stand in as ±0 or ±1
+    fn stand_in(&self) -> f64 {
+        let sign = match self.is_negative {
+            true => -1.0,
+            false => 1.0,
+        };
+        match self.kind {
+            Kind::NaN => f64::NAN,
+            Kind::Infinite => sign * f64::INFINITY,
+            Kind::Finite if (self.significand == 0) => sign * 0.0,
+            Kind::Finite => sign,
+        }
+    }
+    // The functions without a version working on the digits
+    fn through_f64(self, function: impl Fn(f64) -> f64) -> Decimal<DIGITS> {
+        Decimal::from(function(self.to_f64().unwrap()))
+    }
+    // The n-th root of a positive finite value, scaled first so the root has two digits more
+    // than it keeps and the exponent divides by n
+    fn root(self, n: u32) -> Decimal<DIGITS> {
+        let n64 = n as i64;
+        let mut shift = (n64 * (DIGITS as i64 + 2) - digit_count(&self.digits()) as i64).max(0);
+        shift += (self.exponent as i64 - shift).rem_euclid(n64);
+        let scaled = self.digits() * power_of_ten(shift as u32);
+        let root = scaled.nth_root(n);
+        let exponent = (self.exponent as i64 - shift) / n64;
+        match (root.pow(n) == scaled) {
+            true => Decimal::round(false, root, exponent),
+            // A digit standing for the rest, so it rounds like the exact root
+            false => Decimal::round(false, root * 10_u32 + 1_u32, exponent - 1),
+        }
+    }
+    // The integer part, moved one away from zero when `away` says so given the dropped
+    // digits and what they are out of
+    fn integer_part(self, away: impl Fn(&BigUint, &BigUint) -> bool) -> Decimal<DIGITS> {
+        if (self.kind != Kind::Finite || self.exponent >= 0) {
+            return self;
+        }
+        // Past the digits 10^dropped only has to be larger than them for every caller
+        let dropped = (-(self.exponent as i64)).min(digit_count(&self.digits()) as i64 + 1);
+        let divisor = power_of_ten(dropped as u32);
+        let (quotient, remainder) = self.digits().div_rem(&divisor);
+        let quotient = match away(&remainder, &divisor) {
+            true => quotient + 1_u32,
+            false => quotient,
+        };
+        Decimal::round(self.is_negative, quotient, 0)
+    }
+}
+// Through the shortest decimal form that reads back as the same f64, so 0.1 is 0.1
+impl<const DIGITS: u32> From<f64> for Decimal<DIGITS> {
+    fn from(value: f64) -> Decimal<DIGITS> {
+        match value.classify() {
+            FpCategory::Nan => Decimal::nan(),
+            FpCategory::Infinite => Decimal::special(Kind::Infinite, value < 0.0),
+            _ => format!("{:e}", value).parse().unwrap(),
+        }
+    }
+}
+impl<const DIGITS: u32> From<i64> for Decimal<DIGITS> {
+    fn from(value: i64) -> Decimal<DIGITS> {
+        Decimal::round(value < 0, BigUint::from(value.unsigned_abs()), 0)
+    }
+}
+// Reads "-12.5", "1.5e-7", "NaN", "inf" and "infinity", rounding to DIGITS digits
+impl<const DIGITS: u32> FromStr for Decimal<DIGITS> {
+    type Err = BigNumberError;
+    fn from_str(s: &str) -> Result<Decimal<DIGITS>, BigNumberError> {
+        let (is_negative, unsigned) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let start = s.len() - unsigned.len();
+        if (unsigned.eq_ignore_ascii_case("nan")) {
+            return Ok(Decimal::nan());
+        }
+        if (unsigned.eq_ignore_ascii_case("inf") || unsigned.eq_ignore_ascii_case("infinity")) {
+            return Ok(Decimal::special(Kind::Infinite, is_negative));
+        }
+        let (number, written_exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((number, exponent)) => (
+                number,
+                exponent.parse::<i64>().map_err(|_| {
+                    BigNumberError::new(ErrorCause::BadExponent, s, Some(start + number.len() + 1))
+                })?,
+            ),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        let invalid = |part: &str, offset: usize| {
+            part.find(|char: char| !char.is_ascii_digit())
+                .map(|index| offset + index)
+        };
+        let bad_digit = invalid(integer, start)
+            .or_else(|| invalid(fraction, start + integer.len() + 1))
+            .or((integer.is_empty() && fraction.is_empty()).then_some(start));
+        if let Some(offset) = bad_digit {
+            return Err(BigNumberError::new(ErrorCause::BadPrefix, s, Some(offset)));
+        }
+        let significand = format!("{}{}", integer, fraction).parse().unwrap();
+        let exponent = written_exponent.saturating_sub(fraction.len() as i64);
+        Ok(Decimal::round(is_negative, significand, exponent))
+    }
+}
+// Writes every digit like f64 does, "1.5" or "-0.001", up to 40 places either side of the
+// point; further out as "1.5e100", so a huge value does not print all of its zeros
+impl<const DIGITS: u32> fmt::Display for Decimal<DIGITS> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            Kind::NaN => return write!(f, "NaN"),
+            Kind::Infinite if (self.is_negative) => return write!(f, "-inf"),
+            Kind::Infinite => return write!(f, "inf"),
+            Kind::Finite => {}
+        }
+        let number = match f.precision() {
+            Some(decimals) => Decimal::<DIGITS>::round_at(
+                self.is_negative,
+                self.digits(),
+                self.exponent as i64,
+                -(decimals as i64),
+            ),
+            None => *self,
+        };
+        if (number.is_negative) {
+            write!(f, "-")?;
+        }
+        let digits = number.significand.to_string();
+        let exponent = number.exponent as i64;
+        let order = exponent + digits.len() as i64 - 1;
+        if (order.abs() > 40) {
+            return match digits.len() {
+                1 => write!(f, "{}e{}", digits, order),
+                _ => write!(f, "{}.{}e{}", &digits[..1], &digits[1..], order),
+            };
+        }
+        let written = match (exponent >= 0, order >= 0) {
+            (true, _) => format!("{}{}", digits, "0".repeat(exponent as usize)),
+            (false, true) => {
+                let point = (order + 1) as usize;
+                format!("{}.{}", &digits[..point], &digits[point..])
+            }
+            (false, false) => format!("0.{}{}", "0".repeat((-order - 1) as usize), digits),
+        };
+        let decimals = written
+            .split_once('.')
+            .map_or(0, |(_, fraction)| fraction.len());
+        match f.precision() {
+            Some(0) | None => write!(f, "{}", written),
+            Some(precision) if (decimals == 0) => {
+                write!(f, "{}.{}", written, "0".repeat(precision))
+            }
+            Some(precision) => write!(f, "{}{}", written, "0".repeat(precision - decimals)),
+        }
+    }
+}
+// Like f64: NaN is unordered and the two zeros are equal
+impl<const DIGITS: u32> PartialEq for Decimal<DIGITS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+impl<const DIGITS: u32> PartialOrd for Decimal<DIGITS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if (self.is_nan() || other.is_nan()) {
+            return None;
+        }
+        let sign = |number: &Decimal<DIGITS>| match (number.is_zero(), number.is_negative) {
+            (true, _) => 0,
+            (false, false) => 1,
+            (false, true) => -1,
+        };
+        if (sign(self) != sign(other) || sign(self) == 0) {
+            return Some(sign(self).cmp(&sign(other)));
+        }
+        let magnitude = match (self.kind, other.kind) {
+            (Kind::Infinite, Kind::Infinite) => Ordering::Equal,
+            (Kind::Infinite, _) => Ordering::Greater,
+            (_, Kind::Infinite) => Ordering::Less,
+            _ => self.order().cmp(&other.order()).then_with(|| {
+                // Same order, so padding both to the longer digit count lines them up
+                let places = self.significand.ilog10().max(other.significand.ilog10());
+                let padded = |number: &Decimal<DIGITS>| {
+                    number.significand * 10_u128.pow(places - number.significand.ilog10())
+                };
+                padded(self).cmp(&padded(other))
+            }),
+        };
+        Some(match self.is_negative {
+            true => magnitude.reverse(),
+            false => magnitude,
+        })
+    }
+}
+impl<const DIGITS: u32> ops::Neg for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn neg(mut self) -> Decimal<DIGITS> {
+        self.is_negative = !self.is_negative;
+        self
+    }
+}
+impl<const DIGITS: u32> ops::Add for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn add(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        if (!self.is_finite() || !other.is_finite()) {
+            return Decimal::from(self.stand_in() + other.stand_in());
+        }
+        if (self.is_zero()) {
+            return match other.is_zero() {
+                true => Decimal::signed_zero(self.is_negative && other.is_negative),
+                false => other,
+            };
+        }
+        if (other.is_zero()) {
+            return self;
+        }
+        // More than DIGITS + 1 places apart the smaller one is below half of the last digit
+        // the larger one can have
+        let (larger, smaller) = match (self.order() >= other.order()) {
+            true => (self, other),
+            false => (other, self),
+        };
+        if (larger.order() - smaller.order() > DIGITS as i64 + 1) {
+            return larger;
+        }
+        let exponent = self.exponent.min(other.exponent) as i64;
+        let aligned = |number: &Decimal<DIGITS>| {
+            number.digits() * power_of_ten((number.exponent as i64 - exponent) as u32)
+        };
+        let (a, b) = (aligned(&self), aligned(&other));
+        if (self.is_negative == other.is_negative) {
+            return Decimal::round(self.is_negative, a + b, exponent);
+        }
+        match a.cmp(&b) {
+            Ordering::Greater => Decimal::round(self.is_negative, a - b, exponent),
+            Ordering::Less => Decimal::round(other.is_negative, b - a, exponent),
+            Ordering::Equal => Decimal::zero(),
+        }
+    }
+}
+impl<const DIGITS: u32> ops::Sub for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn sub(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        self + (-other)
+    }
+}
+impl<const DIGITS: u32> ops::Mul for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn mul(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        if (!self.is_finite() || !other.is_finite()) {
+            return Decimal::from(self.stand_in() * other.stand_in());
+        }
+        Decimal::round(
+            self.is_negative != other.is_negative,
+            self.digits() * other.digits(),
+            self.exponent as i64 + other.exponent as i64,
+        )
+    }
+}
+impl<const DIGITS: u32> ops::Div for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn div(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        if (!self.is_finite() || !other.is_finite() || other.is_zero()) {
+            return Decimal::from(self.stand_in() / other.stand_in());
+        }
+        let is_negative = self.is_negative != other.is_negative;
+        // Two digits more than it keeps, and a third standing for any remainder
+        let shift = (DIGITS as i64 + 2 + digit_count(&other.digits()) as i64
+            - digit_count(&self.digits()) as i64)
+            .max(0);
+        let (quotient, remainder) =
+            (self.digits() * power_of_ten(shift as u32)).div_rem(&other.digits());
+        let exponent = self.exponent as i64 - other.exponent as i64 - shift;
+        match remainder.is_zero() {
+            true => Decimal::round(is_negative, quotient, exponent),
+            false => Decimal::round(is_negative, quotient * 10_u32 + 1_u32, exponent - 1),
+        }
+    }
+}
+// Exact, with the sign of the dividend like the primitive `%`
+impl<const DIGITS: u32> ops::Rem for Decimal<DIGITS> {
+    type Output = Decimal<DIGITS>;
+    fn rem(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        if (self.is_finite() && other.is_infinite()) {
+            return self;
+        }
+        if (!self.is_finite() || !other.is_finite() || other.is_zero()) {
+            return Decimal::from(self.stand_in() % other.stand_in());
+        }
+        if (self.is_zero() || self.order() < other.order()) {
+            return self;
+        }
+        let divisor = other.digits();
+        let (remainder, exponent) = match (self.exponent >= other.exponent) {
+            // 10^difference mod divisor, without writing out the billions of digits it can have
+            true => {
+                let difference =
+                    BigUint::from((self.exponent as i64 - other.exponent as i64) as u64);
+                let shifted = BigUint::from(10_u32).modpow(&difference, &divisor);
+                ((self.digits() * shifted) % &divisor, other.exponent)
+            }
+            false => {
+                let difference = (other.exponent as i64 - self.exponent as i64) as u32;
+                (
+                    self.digits() % (divisor * power_of_ten(difference)),
+                    self.exponent,
+                )
+            }
+        };
+        Decimal::round(self.is_negative, remainder, exponent as i64)
+    }
+}
+impl<const DIGITS: u32> Zero for Decimal<DIGITS> {
+    fn zero() -> Decimal<DIGITS> {
+        Decimal::signed_zero(false)
+    }
+    fn is_zero(&self) -> bool {
+        self.kind == Kind::Finite && self.significand == 0
+    }
+}
+impl<const DIGITS: u32> One for Decimal<DIGITS> {
+    fn one() -> Decimal<DIGITS> {
+        Decimal {
+            significand: 1,
+            ..Decimal::zero()
+        }
+    }
+}
+impl<const DIGITS: u32> Num for Decimal<DIGITS> {
+    type FromStrRadixErr = BigNumberError;
+    // Only base 10 has the digits a Decimal keeps
+    fn from_str_radix(x: &str, radix: u32) -> Result<Decimal<DIGITS>, BigNumberError> {
+        match radix {
+            10 => x.parse(),
+            _ => Err(BigNumberError::new(ErrorCause::BadPrefix, x, None)),
+        }
+    }
+}
+impl<const DIGITS: u32> ToPrimitive for Decimal<DIGITS> {
+    fn to_i64(&self) -> Option<i64> {
+        self.to_i128()?.try_into().ok()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i128()?.try_into().ok()
+    }
+    // The integer part, when it fits
+    fn to_i128(&self) -> Option<i128> {
+        if (!self.is_finite()) {
+            return None;
+        }
+        let integer = self.trunc();
+        let magnitude = i128::try_from(integer.significand)
+            .ok()?
+            .checked_mul(10_i128.checked_pow(integer.exponent.try_into().ok()?)?)?;
+        Some(match integer.is_negative {
+            true => -magnitude,
+            false => magnitude,
+        })
+    }
+    // The f64 nearest to the digits
+    fn to_f64(&self) -> Option<f64> {
+        if (!self.is_finite()) {
+            return Some(self.stand_in());
+        }
+        let magnitude = format!("{}e{}", self.significand, self.exponent)
+            .parse::<f64>()
+            .ok()?;
+        Some(match self.is_negative {
+            true => -magnitude,
+            false => magnitude,
+        })
+    }
+}
+// Through the f64's shortest decimal form, like `From<f64>`
+impl<const DIGITS: u32> num::traits::NumCast for Decimal<DIGITS> {
+    fn from<N: ToPrimitive>(n: N) -> Option<Decimal<DIGITS>> {
+        n.to_f64().map(<Decimal<DIGITS> as From<f64>>::from)
+    }
+}
+macro_rules! impl_through_f64 {
+    ($($method:ident),*) => {
+        $(
+            fn $method(self) -> Decimal<DIGITS> {
+                self.through_f64(f64::$method)
+            }
+        )*
+    };
+}
+impl<const DIGITS: u32> Float for Decimal<DIGITS> {
+    fn nan() -> Decimal<DIGITS> {
+        Decimal::special(Kind::NaN, false)
+    }
+    fn infinity() -> Decimal<DIGITS> {
+        Decimal::special(Kind::Infinite, false)
+    }
+    fn neg_infinity() -> Decimal<DIGITS> {
+        Decimal::special(Kind::Infinite, true)
+    }
+    fn neg_zero() -> Decimal<DIGITS> {
+        Decimal::signed_zero(true)
+    }
+    fn min_value() -> Decimal<DIGITS> {
+        -Decimal::max_value()
+    }
+    fn min_positive_value() -> Decimal<DIGITS> {
+        Decimal {
+            exponent: i32::MIN,
+            ..Decimal::one()
+        }
+    }
+    // DIGITS nines, the last of them at 10^i32::MAX
+    fn max_value() -> Decimal<DIGITS> {
+        Decimal {
+            significand: 10_u128.pow(DIGITS) - 1,
+            exponent: i32::MAX - (DIGITS as i32 - 1),
+            ..Decimal::zero()
+        }
+    }
+    fn epsilon() -> Decimal<DIGITS> {
+        Decimal {
+            exponent: 1 - DIGITS as i32,
+            ..Decimal::one()
+        }
+    }
+    fn is_nan(self) -> bool {
+        self.kind == Kind::NaN
+    }
+    fn is_infinite(self) -> bool {
+        self.kind == Kind::Infinite
+    }
+    fn is_finite(self) -> bool {
+        self.kind == Kind::Finite
+    }
+    fn is_normal(self) -> bool {
+        self.is_finite() && !self.is_zero()
+    }
+    fn classify(self) -> FpCategory {
+        match self.kind {
+            Kind::NaN => FpCategory::Nan,
+            Kind::Infinite => FpCategory::Infinite,
+            Kind::Finite if (self.is_zero()) => FpCategory::Zero,
+            Kind::Finite => FpCategory::Normal,
+        }
+    }
+    fn floor(self) -> Decimal<DIGITS> {
+        let is_negative = self.is_negative;
+        self.integer_part(|remainder, _| is_negative && !remainder.is_zero())
+    }
+    fn ceil(self) -> Decimal<DIGITS> {
+        let is_negative = self.is_negative;
+        self.integer_part(|remainder, _| !is_negative && !remainder.is_zero())
+    }
+    // Halfway cases away from zero, like f64
+    fn round(self) -> Decimal<DIGITS> {
+        self.integer_part(|remainder, divisor| remainder * 2_u32 >= *divisor)
+    }
+    fn trunc(self) -> Decimal<DIGITS> {
+        self.integer_part(|_, _| false)
+    }
+    fn fract(self) -> Decimal<DIGITS> {
+        self - self.trunc()
+    }
+    fn abs(mut self) -> Decimal<DIGITS> {
+        self.is_negative = false;
+        self
+    }
+    fn signum(self) -> Decimal<DIGITS> {
+        match (self.is_nan(), self.is_negative) {
+            (true, _) => self,
+            (false, true) => -Decimal::one(),
+            (false, false) => Decimal::one(),
+        }
+    }
+    fn is_sign_positive(self) -> bool {
+        !self.is_negative
+    }
+    fn is_sign_negative(self) -> bool {
+        self.is_negative
+    }
+    fn mul_add(self, a: Decimal<DIGITS>, b: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        self * a + b
+    }
+    fn recip(self) -> Decimal<DIGITS> {
+        Decimal::one() / self
+    }
+    // By squaring, rounding after every product like f64
+    fn powi(self, n: i32) -> Decimal<DIGITS> {
+        // Powers of ten stay exact, with the exponent in an i64 so 10^-2147483648 does not
+        // go through an infinite 10^2147483648
+        if (self.is_finite() && self.significand == 1) {
+            let is_negative = self.is_negative && n % 2 != 0;
+            let exponent = self.exponent as i64 * n as i64;
+            return Decimal::from_digits(is_negative, BigUint::one(), exponent);
+        }
+        let mut result = Decimal::one();
+        let mut square = self;
+        let mut remaining = n.unsigned_abs();
+        while (remaining > 0) {
+            if (remaining & 1 == 1) {
+                result = result * square;
+            }
+            remaining >>= 1;
+            if (remaining > 0) {
+                square = square * square;
+            }
+        }
+        match (n < 0) {
+            true => result.recip(),
+            false => result,
+        }
+    }
+    fn powf(self, n: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        match n.to_i32() {
+            Some(power) if (Decimal::from(power as i64) == n) => self.powi(power),
+            _ => Decimal::from(self.to_f64().unwrap().powf(n.to_f64().unwrap())),
+        }
+    }
+    fn sqrt(self) -> Decimal<DIGITS> {
+        if (self.is_nan() || self.is_zero()) {
+            return self;
+        }
+        match (self.is_negative, self.is_infinite()) {
+            (true, _) => Decimal::nan(),
+            (false, true) => self,
+            (false, false) => self.root(2),
+        }
+    }
+    fn cbrt(self) -> Decimal<DIGITS> {
+        if (!self.is_finite() || self.is_zero()) {
+            return self;
+        }
+        let root = self.abs().root(3);
+        match self.is_negative {
+            true => -root,
+            false => root,
+        }
+    }
+    fn log(self, base: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        let base = base.to_f64().unwrap();
+        self.through_f64(|value| value.log(base))
+    }
+    // Through f64 as well, but with the integer part taken from the exponent so the floor is
+    // exact, which moving a mantissa into [1, 10) relies on
+    fn log10(self) -> Decimal<DIGITS> {
+        if (!self.is_finite() || self.is_zero() || self.is_negative) {
+            return Decimal::from(self.stand_in().log10());
+        }
+        let order = self.order();
+        let leading = Decimal {
+            exponent: self.exponent - order as i32,
+            ..self
+        };
+        let fraction = leading
+            .to_f64()
+            .unwrap()
+            .log10()
+            .min(1.0 - f64::EPSILON / 2.0);
+        let logarithm = Decimal::from(order) + Decimal::from(fraction);
+        if (logarithm.floor() == Decimal::from(order)) {
+            return logarithm;
+        }
+        // Rounded up to order + 1: just below it instead
+        let next = order + 1;
+        let places = DIGITS as i64 - 1 - digit_count(&BigUint::from(next.unsigned_abs())) as i64;
+        let scaled = BigUint::from(next.unsigned_abs()) * power_of_ten(places as u32);
+        match (next > 0) {
+            true => Decimal::round(false, scaled - 1_u32, -places),
+            false => Decimal::round(true, scaled + 1_u32, -places),
+        }
+    }
+    fn max(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        match (self.is_nan() || other > self) {
+            true => other,
+            false => self,
+        }
+    }
+    fn min(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        match (self.is_nan() || other < self) {
+            true => other,
+            false => self,
+        }
+    }
+    fn abs_sub(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        match (self <= other) {
+            true => Decimal::zero(),
+            false => self - other,
+        }
+    }
+    fn hypot(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        (self * self + other * other).sqrt()
+    }
+    fn atan2(self, other: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        let other = other.to_f64().unwrap();
+        self.through_f64(|value| value.atan2(other))
+    }
+    fn sin_cos(self) -> (Decimal<DIGITS>, Decimal<DIGITS>) {
+        (self.sin(), self.cos())
+    }
+    // Of the nearest f64, so equal values give equal results for hashing
+    fn integer_decode(self) -> (u64, i16, i8) {
+        self.to_f64().unwrap().integer_decode()
+    }
+    impl_through_f64!(
+        exp, exp2, ln, log2, sin, cos, tan, asin, acos, atan, exp_m1, ln_1p, sinh, cosh, tanh,
+        asinh, acosh, atanh
+    );
+}
+impl<const DIGITS: u32> Mantissa for Decimal<DIGITS> {
+    type Wide = Decimal<DIGITS>;
+    fn widen(self) -> Decimal<DIGITS> {
+        self
+    }
+    fn narrow(wide: Decimal<DIGITS>) -> Decimal<DIGITS> {
+        wide
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_number::BigNumber;
+    fn decimal(written: &str) -> Decimal {
+        written.parse().unwrap()
+    }
+    #[test]
+    fn keeps_decimal_fractions_exact() {
+        let tenth = decimal("0.1");
+        let mut total = Decimal::zero();
+        for _ in 0..1000 {
+            total = total + tenth;
+        }
+        assert_eq!(total, decimal("100"));
+        assert_eq!(decimal("0.1") + decimal("0.2"), decimal("0.3"));
+        assert_eq!(Decimal::<34>::from(0.1).to_string(), "0.1");
+        assert_eq!(
+            (decimal("1") / decimal("3")).to_string(),
+            "0.3333333333333333333333333333333333"
+        );
+        assert_eq!(
+            (decimal("2") / decimal("3")).to_string(),
+            "0.6666666666666666666666666666666667"
+        );
+        assert_eq!(decimal("-12.50").to_string(), "-12.5");
+        assert_eq!(decimal("1.5e100").to_string(), "1.5e100");
+        assert_eq!(decimal("7.25") % decimal("2"), decimal("1.25"));
+        assert_eq!(decimal("1e1000000") % decimal("7"), decimal("4"));
+        assert_eq!(format!("{:.2}", decimal("2.675")), "2.68");
+        let error = "1.2.3".parse::<Decimal>().unwrap_err();
+        assert_eq!(
+            (error.cause, error.offset),
+            (ErrorCause::BadPrefix, Some(3))
+        );
+    }
+    #[test]
+    fn rounds_half_to_even_at_the_last_digit() {
+        let short = |written: &str| written.parse::<Decimal<16>>().unwrap().to_string();
+        assert_eq!(short("1.0000000000000005"), "1");
+        assert_eq!(short("1.0000000000000015"), "1.000000000000002");
+        assert_eq!(short("1.00000000000000050001"), "1.000000000000001");
+        assert_eq!(short("9999999999999999.5"), "10000000000000000");
+        let sum = decimal("1e34") + decimal("0.5") - decimal("1e34");
+        assert!(sum.is_zero());
+    }
+    #[test]
+    fn works_out_roots_and_powers_on_the_digits() {
+        assert_eq!(
+            decimal("2").sqrt().to_string(),
+            "1.414213562373095048801688724209698"
+        );
+        assert_eq!(decimal("-27").cbrt(), decimal("-3"));
+        assert_eq!(decimal("10").powi(-3), decimal("0.001"));
+        assert_eq!(decimal("10").powi(i32::MIN), Decimal::min_positive_value());
+        assert_eq!(decimal("-0.1").powi(3), decimal("-0.001"));
+        assert_eq!(decimal("1.1").powf(decimal("2")), decimal("1.21"));
+        assert_eq!(decimal("-2.5").floor(), decimal("-3"));
+        assert_eq!(decimal("-2.5").round(), decimal("-3"));
+        assert_eq!(decimal("2.5").trunc(), decimal("2"));
+        let below_ten = decimal("9.999999999999999999999999999999999");
+        assert_eq!(below_ten.log10().floor(), Decimal::zero());
+        assert_eq!(decimal("1000").log10(), decimal("3"));
+    }
+    #[test]
+    fn follows_ieee_for_special_values() {
+        let one = Decimal::<34>::one();
+        assert_eq!(one / Decimal::zero(), Decimal::infinity());
+        assert_eq!(one / Decimal::neg_zero(), Decimal::neg_infinity());
+        assert!((Decimal::<34>::zero() / Decimal::zero()).is_nan());
+        assert!((Decimal::<34>::infinity() - Decimal::infinity()).is_nan());
+        assert!(decimal("-4").sqrt().is_nan());
+        assert_eq!(Decimal::<34>::neg_zero(), Decimal::zero());
+        assert!(Decimal::<34>::max_value() * decimal("10") == Decimal::infinity());
+        assert!((Decimal::<34>::min_positive_value() / decimal("10")).is_zero());
+        assert_eq!(decimal("-inf").to_string(), "-inf");
+    }
+    #[test]
+    fn gives_big_numbers_an_exact_mantissa() {
+        let position: BigNumber<Decimal> = "1.49597870700x10^11".parse().unwrap();
+        let half = BigNumber::new_d(decimal("0.5"));
+        let nudged = position + half - half;
+        assert_eq!(nudged, position);
+        let step: BigNumber<Decimal> = "0.1".parse().unwrap();
+        let mut total = BigNumber::new_d(Decimal::zero());
+        for _ in 0..1000 {
+            total += step;
+        }
+        assert_eq!(total.to_string(), "100");
+        let summed: BigNumber<Decimal> = std::iter::repeat_n(&step, 1000).sum();
+        assert_eq!(summed, total);
+        // The sum binary mantissas drift on
+        let mut float_total = BigNumber::new_d(0.0_f32);
+        for _ in 0..1000 {
+            float_total += 0.1;
+        }
+        assert_ne!(float_total, BigNumber::new_d(100.0));
+        let third = BigNumber::new_d(decimal("1")) / BigNumber::new_d(decimal("3"));
+        assert_eq!(
+            third.base.to_string(),
+            "3.333333333333333333333333333333333"
+        );
+        assert_eq!(third.exponent, -1);
+        let huge: BigNumber<Decimal> = "4x10^1000".parse().unwrap();
+        assert_eq!(huge.sqrt().to_string(), "2x10^500");
+        let smallest: BigNumber<Decimal> = BigNumber::new_d(Decimal::min_positive_value());
+        assert_eq!(smallest, BigNumber::min_positive_value());
+        assert_eq!(
+            BigNumber::<f32>::new_d(1.1).cast::<Decimal>().base,
+            decimal("1.1")
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use super::{active_table, write_mantissa, BigNumber, Mantissa, Notation};
+use std::{cell::RefCell, fmt};
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    // Towards negative infinity, so -1.25 becomes -1.3 at two digits
+    Floor,
+    // To the nearest digit, ties to the even one
+    HalfEven,
+    // Towards zero, dropping the digits that do not fit
+    Truncate,
+}
+// How numbers are written when they are displayed. A BigNumber only holds its value, so
+// the same number can be shown differently in different places without being rebuilt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatPolicy {
+    preferred: Notation,
+    // Used for exponents outside `thresholds`
+    fallback: Notation,
+    // The lowest and highest exponent written in `preferred`. None is everything `preferred`
+    // can write: the active suffix table for Haven, any exponent otherwise
+    thresholds: Option<(i32, i32)>,
+    // None keeps every digit the mantissa has
+    significant_digits: Option<u32>,
+    rounding: RoundingMode,
+    // Pads the mantissa with zeros up to `significant_digits`, "1.500QD"
+    trailing_zeros: bool,
+    // How many exponents past a threshold a value has to get before a `FormatState`
+    // switches it back to `preferred`
+    hysteresis: i32,
+}
+impl FormatPolicy {
+    pub fn new(preferred: Notation) -> FormatPolicy {
+        FormatPolicy {
+            preferred,
+            fallback: Notation::Scientific,
+            thresholds: None,
+            significant_digits: None,
+            rounding: RoundingMode::HalfEven,
+            trailing_zeros: false,
+            hysteresis: 0,
+        }
+    }
+    pub fn with_preferred(mut self, preferred: Notation) -> FormatPolicy {
+        self.preferred = preferred;
+        self
+    }
+    pub fn with_fallback(mut self, fallback: Notation) -> FormatPolicy {
+        self.fallback = fallback;
+        self
+    }
+    pub fn with_thresholds(mut self, lowest: i32, highest: i32) -> FormatPolicy {
+        self.thresholds = Some((lowest, highest));
+        self
+    }
+    pub fn with_significant_digits(mut self, digits: u32) -> FormatPolicy {
+        self.significant_digits = Some(digits.max(1));
+        self
+    }
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> FormatPolicy {
+        self.rounding = rounding;
+        self
+    }
+    pub fn with_trailing_zeros(mut self, trailing_zeros: bool) -> FormatPolicy {
+        self.trailing_zeros = trailing_zeros;
+        self
+    }
+    pub fn with_hysteresis(mut self, hysteresis: i32) -> FormatPolicy {
+        self.hysteresis = hysteresis.max(0);
+        self
+    }
+    pub fn preferred(&self) -> &Notation {
+        &self.preferred
+    }
+    fn thresholds(&self) -> (i32, i32) {
+        self.thresholds.unwrap_or_else(|| match self.preferred {
+            Notation::Haven => (0, active_table().exponent_limit() - 1),
+            _ => (i32::MIN, i32::MAX),
+        })
+    }
+    // A value last written in the fallback has to get `hysteresis` exponents inside the
+    // thresholds before it is written in `preferred` again
+    fn uses_preferred(&self, exponent: i32, was_preferred: Option<bool>) -> bool {
+        let (lowest, highest) = self.thresholds();
+        match was_preferred {
+            Some(false) => {
+                lowest.saturating_add(self.hysteresis) <= exponent
+                    && exponent <= highest.saturating_sub(self.hysteresis)
+            }
+            _ => lowest <= exponent && exponent <= highest,
+        }
+    }
+    // The notation a value with this exponent is written in, when there is no earlier one
+    pub(super) fn notation_for(&self, exponent: i32) -> &Notation {
+        match self.uses_preferred(exponent, None) {
+            true => &self.preferred,
+            false => &self.fallback,
+        }
+    }
+    // Rounds a "d.ddd" mantissa to the significant digits, returning the new mantissa and
+    // how far the exponent moves (1 when 9.99 rounds up to 10)
+    fn round_mantissa(&self, mantissa: &str, is_positive: bool) -> (String, i32) {
+        let digits: Vec<u8> = mantissa
+            .bytes()
+            .filter(|byte| byte.is_ascii_digit())
+            .map(|byte| byte - b'0')
+            .collect();
+        let count = self
+            .significant_digits
+            .map_or(digits.len(), |digits| digits as usize);
+        let (kept, rest) = digits.split_at(count.min(digits.len()));
+        let mut kept = kept.to_vec();
+        let is_dropping = rest.iter().any(|digit| *digit != 0);
+        let round_up = match self.rounding {
+            RoundingMode::Truncate => false,
+            RoundingMode::Floor => !is_positive && is_dropping,
+            RoundingMode::HalfEven => match rest.split_first() {
+                Some((first, after)) => {
+                    *first > 5
+                        || (*first == 5
+                            && (after.iter().any(|digit| *digit != 0)
+                                || kept.last().is_some_and(|digit| digit % 2 == 1)))
+                }
+                None => false,
+            },
+        };
+        let mut shift = 0;
+        if (round_up) {
+            match kept.iter().rposition(|digit| *digit != 9) {
+                Some(index) => {
+                    kept[index] += 1;
+                    kept[index + 1..].fill(0);
+                }
+                None => {
+                    kept.fill(0);
+                    kept.insert(0, 1);
+                    kept.pop();
+                    shift = 1;
+                }
+            }
+        }
+        match self.trailing_zeros {
+            true => kept.resize(kept.len().max(count), 0),
+            false => {
+                while (kept.len() > 1 && kept.last() == Some(&0)) {
+                    kept.pop();
+                }
+            }
+        }
+        let written: String = kept.iter().map(|digit| char::from(b'0' + digit)).collect();
+        match written.len() {
+            1 => (written, shift),
+            _ => (format!("{}.{}", &written[..1], &written[1..]), shift),
+        }
+    }
+    // The signed number and whether it was written in `preferred`
+    fn write<T: Mantissa>(
+        &self,
+        number: &BigNumber<T>,
+        was_preferred: Option<bool>,
+    ) -> (String, bool) {
+        let sign = match number.is_positive {
+            true => "",
+            false => "-",
+        };
+        if (number.is_zero() || !number.is_finite()) {
+            return (
+                format!("{}{}", sign, number.to_format(&self.preferred)),
+                true,
+            );
+        }
+        let base = number.base.to_string();
+        let (mut mantissa, shift) = self.round_mantissa(&base, number.is_positive);
+        // Nothing to round up into past 10^2147483647
+        let exponent = number.exponent.checked_add(shift).unwrap_or_else(|| {
+            mantissa = self
+                .clone()
+                .with_rounding(RoundingMode::Truncate)
+                .round_mantissa(&base, number.is_positive)
+                .0;
+            number.exponent
+        });
+        let is_preferred = self.uses_preferred(exponent, was_preferred);
+        let notation = match is_preferred {
+            true => &self.preferred,
+            false => &self.fallback,
+        };
+        let written = write_mantissa(&mantissa, exponent, notation);
+        (format!("{}{}", sign, written), is_preferred)
+    }
+    pub fn format<T: Mantissa>(&self, number: &BigNumber<T>) -> String {
+        self.display(number).to_string()
+    }
+    pub fn display<'a, T: Mantissa>(&'a self, number: &'a BigNumber<T>) -> Formatted<'a, T> {
+        Formatted {
+            number,
+            policy: self,
+        }
+    }
+}
+// Haven while the active suffix table has a suffix, Scientific past it, every digit kept
+impl Default for FormatPolicy {
+    fn default() -> FormatPolicy {
+        FormatPolicy::new(Notation::Haven)
+    }
+}
+// A number together with the policy it is written with, formatted only when displayed
+pub struct Formatted<'a, T: Mantissa> {
+    number: &'a BigNumber<T>,
+    policy: &'a FormatPolicy,
+}
+impl<T: Mantissa> fmt::Display for Formatted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.policy.write(self.number, None).0)
+    }
+}
+impl<T: Mantissa> BigNumber<T> {
+    pub fn display_with<'a>(&'a self, policy: &'a FormatPolicy) -> Formatted<'a, T> {
+        policy.display(self)
+    }
+}
+// Remembers which notation one displayed value was last written in, so that with hysteresis
+// a value hovering around a threshold does not flicker between notations
+#[derive(Debug, Clone)]
+pub struct FormatState {
+    policy: FormatPolicy,
+    was_preferred: Option<bool>,
+}
+impl FormatState {
+    pub fn new(policy: FormatPolicy) -> FormatState {
+        FormatState {
+            policy,
+            was_preferred: None,
+        }
+    }
+    pub fn policy(&self) -> &FormatPolicy {
+        &self.policy
+    }
+    pub fn format<T: Mantissa>(&mut self, number: &BigNumber<T>) -> String {
+        let (written, is_preferred) = self.policy.write(number, self.was_preferred);
+        self.was_preferred = Some(is_preferred);
+        written
+    }
+}
+thread_local! {
+    static DISPLAY_POLICY: RefCell<FormatPolicy> = RefCell::new(FormatPolicy::default());
+}
+// Makes `policy` the one `Display` and `get_value` write every BigNumber on this thread with,
+// returning the policy it replaces
+pub fn set_display_policy(policy: FormatPolicy) -> FormatPolicy {
+    DISPLAY_POLICY.with(|active| active.replace(policy))
+}
+pub fn display_policy() -> FormatPolicy {
+    DISPLAY_POLICY.with(|active| active.borrow().clone())
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn writes_the_same_value_in_each_notation() {
+        let number = "-1.496x10^11".parse::<BigNumber>().unwrap();
+        let written = |notation: Notation| FormatPolicy::new(notation).format(&number);
+        assert_eq!(number.to_string(), "-149.6B");
+        assert_eq!(written(Notation::Haven), "-149.6B");
+        assert_eq!(written(Notation::Scientific), "-1.496x10^11");
+        assert_eq!(written(Notation::Engineering), "-149.6x10^9");
+        assert_eq!(written(Notation::SI("m".to_string())), "-149.6 Gm");
+        assert_eq!(
+            FormatPolicy::default().format(&BigNumber::<f32>::nan()),
+            "NaN"
+        );
+    }
+    #[test]
+    fn rounds_to_significant_digits() {
+        let policy = |rounding| {
+            FormatPolicy::new(Notation::Scientific)
+                .with_significant_digits(3)
+                .with_rounding(rounding)
+        };
+        for (written, floor, half_even, truncate) in [
+            ("1.2345", "1.23x10^0", "1.23x10^0", "1.23x10^0"),
+            ("-1.2345", "-1.24x10^0", "-1.23x10^0", "-1.23x10^0"),
+            ("1.235", "1.23x10^0", "1.24x10^0", "1.23x10^0"),
+            ("1.225", "1.22x10^0", "1.22x10^0", "1.22x10^0"),
+            ("1.2251", "1.22x10^0", "1.23x10^0", "1.22x10^0"),
+            ("-9.996x10^4", "-1x10^5", "-1x10^5", "-9.99x10^4"),
+            ("2.3", "2.3x10^0", "2.3x10^0", "2.3x10^0"),
+        ] {
+            let number = written.parse::<BigNumber<f64>>().unwrap();
+            assert_eq!(policy(RoundingMode::Floor).format(&number), floor);
+            assert_eq!(policy(RoundingMode::HalfEven).format(&number), half_even);
+            assert_eq!(policy(RoundingMode::Truncate).format(&number), truncate);
+        }
+        let padded = FormatPolicy::new(Notation::Haven)
+            .with_significant_digits(4)
+            .with_trailing_zeros(true);
+        assert_eq!(
+            padded.format(&"1.5QD".parse::<BigNumber>().unwrap()),
+            "1.500QD"
+        );
+        assert_eq!(
+            padded.format(&"15QD".parse::<BigNumber>().unwrap()),
+            "15.00QD"
+        );
+        assert_eq!(
+            padded.format(&"-999.96SP".parse::<BigNumber>().unwrap()),
+            "-1.000x10^27"
+        );
+        let max = "9.99x10^2147483647".parse::<BigNumber>().unwrap();
+        assert_eq!(
+            policy(RoundingMode::HalfEven)
+                .with_significant_digits(2)
+                .format(&max),
+            "9.9x10^2147483647"
+        );
+    }
+    #[test]
+    fn switches_at_thresholds_with_hysteresis() {
+        let policy = FormatPolicy::new(Notation::Haven)
+            .with_thresholds(0, 11)
+            .with_hysteresis(2);
+        let number = |exponent: i32| {
+            let mut number = BigNumber::new_d(2.0_f32);
+            number.increase_power(exponent).unwrap();
+            number
+        };
+        assert_eq!(policy.format(&number(11)), "200B");
+        assert_eq!(policy.format(&number(12)), "2x10^12");
+        let mut state = FormatState::new(policy);
+        let written: Vec<String> = [11, 12, 11, 10, 9, 10, 12]
+            .iter()
+            .map(|exponent| state.format(&number(*exponent)))
+            .collect();
+        assert_eq!(
+            written,
+            ["200B", "2x10^12", "2x10^11", "2x10^10", "2B", "20B", "2x10^12"]
+        );
+    }
+    #[test]
+    fn display_uses_the_thread_policy() {
+        let number = "1.23456QD".parse::<BigNumber>().unwrap();
+        let previous =
+            set_display_policy(FormatPolicy::new(Notation::Engineering).with_significant_digits(2));
+        assert_eq!(number.to_string(), "1.2x10^15");
+        assert_eq!(number.get_value(), "1.2x10^15");
+        set_display_policy(previous);
+        assert_eq!(number.to_string(), "1.23456QD");
+    }
+}
@@ -0,0 +1,234 @@
+use super::{from_f64, sign_from_bool, BigNumber, Mantissa, Unbounded};
+use num::traits::{Float, One, ToPrimitive};
+use std::f64::consts::{LN_10, LOG10_E};
+// Roots and integer powers work on the mantissa and exponent separately, so they are as
+// accurate as the operation on the wide mantissa (powi loses about one ulp per squaring).
+// Logarithms are exact in the exponent and add the f64 log of the mantissa. powf and exp go
+// through a base-10 logarithm l of the result: its fraction only keeps 53 - log2(|l|) bits,
+// so the relative error grows to about |l| * 2.6e-16, e.g. 1e-10 for results near 10^(10^6).
+// Outside their domain they give NaN, and at a pole an infinity, as f64 does; results past the
+// exponent range become infinite or zero like the arithmetic operators.
+impl<T: Mantissa> BigNumber<T> {
+    // 10^l with the given sign
+    fn from_log10(l: f64, is_positive: bool) -> BigNumber<T> {
+        let sign = sign_from_bool(is_positive) as f64;
+        if (l.is_infinite()) {
+            return BigNumber::from_parts(from_f64(sign * l.max(0.0)), 0);
+        }
+        let exponent = l.floor();
+        let base = sign * 10.0_f64.powf(l - exponent);
+        Unbounded::new(from_f64(base), exponent as i64).to_big_number()
+    }
+    // The value as a plain float, infinite or zero when it does not fit
+    pub fn to_float(self) -> T {
+        if (self.is_zero()) {
+            return T::zero();
+        }
+        T::narrow(self.signed_base() * from_f64::<T::Wide>(10.0).powi(self.exponent))
+    }
+    pub fn sqrt(&self) -> BigNumber<T> {
+        if (!self.is_positive && !self.is_zero()) {
+            return BigNumber::nan();
+        }
+        let odd = self.exponent.rem_euclid(2);
+        BigNumber::from_parts(
+            (self.signed_base() * from_f64::<T::Wide>(10.0).powi(odd)).sqrt(),
+            (self.exponent - odd) / 2,
+        )
+    }
+    pub fn cbrt(&self) -> BigNumber<T> {
+        // In i64: i32::MIN leaves a remainder of 1 and one less would not fit
+        let remainder = self.exponent.rem_euclid(3);
+        Unbounded::new(
+            (self.signed_base() * from_f64::<T::Wide>(10.0).powi(remainder)).cbrt(),
+            (self.exponent as i64 - remainder as i64) / 3,
+        )
+        .to_big_number()
+    }
+    pub fn powi(&self, n: i32) -> BigNumber<T> {
+        self.unbounded_powi(n).to_big_number()
+    }
+    // By squaring. The exponent stays in an i64 throughout: it is at most |n| * 2^31 plus a
+    // carry per step, so a result past the i32 range is reported as that rather than wrapping
+    pub(super) fn unbounded_powi(&self, n: i32) -> Unbounded<T::Wide> {
+        // ±inf, with the sign of the zero for odd powers
+        if (n < 0 && self.is_zero()) {
+            return Unbounded::new(self.signed_base().powi(n), 0);
+        }
+        let mut result = Unbounded::new(T::Wide::one(), 0);
+        let mut square = Unbounded::from(*self);
+        let mut remaining = n.unsigned_abs();
+        while (remaining > 0) {
+            if (remaining & 1 == 1) {
+                result = result.times(square);
+            }
+            remaining >>= 1;
+            if (remaining > 0) {
+                square = square.times(square);
+            }
+        }
+        match (n < 0) {
+            true => result.reciprocal(),
+            false => result,
+        }
+    }
+    pub fn powf(&self, n: T) -> BigNumber<T> {
+        let power = n.to_f64().unwrap();
+        // x^0 and 1^y are 1 even for NaN, as for f64
+        if (power == 0.0 || *self == BigNumber::new_d(T::one())) {
+            return BigNumber::new_d(T::one());
+        }
+        if (n.is_nan() || self.is_nan()) {
+            return BigNumber::nan();
+        }
+        if (power.fract() == 0.0 && power.abs() <= i32::MAX as f64) {
+            return self.powi(power as i32);
+        }
+        if (self.is_zero()) {
+            return match (power > 0.0) {
+                true => BigNumber::new_d(T::zero()),
+                false => BigNumber::infinity(),
+            };
+        }
+        if (!self.is_positive) {
+            return BigNumber::nan();
+        }
+        BigNumber::from_log10(power * self.log10_f64(), true)
+    }
+    fn log10_f64(&self) -> f64 {
+        if (self.is_zero()) {
+            return f64::NEG_INFINITY;
+        }
+        if (!self.is_positive) {
+            return f64::NAN;
+        }
+        self.exponent as f64 + self.base.to_f64().unwrap().log10()
+    }
+    pub fn log10(&self) -> BigNumber<T> {
+        BigNumber::from_parts(from_f64(self.log10_f64()), 0)
+    }
+    pub fn ln(&self) -> BigNumber<T> {
+        BigNumber::from_parts(from_f64(self.log10_f64() * LN_10), 0)
+    }
+    pub fn exp(&self) -> BigNumber<T> {
+        if (self.is_infinite()) {
+            return match self.is_positive {
+                true => BigNumber::infinity(),
+                false => BigNumber::new_d(T::zero()),
+            };
+        }
+        let power =
+            self.signed_base().to_f64().unwrap() * 10.0_f64.powi(self.exponent.clamp(-400, 400));
+        BigNumber::from_log10(power * LOG10_E, true)
+    }
+    // sqrt(self^2 + other^2) without squaring either value
+    pub fn hypot(&self, other: &BigNumber<T>) -> BigNumber<T> {
+        if (self.is_zero() || other.is_zero()) {
+            let nonzero = match self.is_zero() {
+                true => other,
+                false => self,
+            };
+            return (*nonzero).abs();
+        }
+        let exponent = self.exponent.max(other.exponent);
+        let ten = from_f64::<T::Wide>(10.0);
+        let scaled = |number: &BigNumber<T>| {
+            number.signed_base() * ten.powi(number.exponent.saturating_sub(exponent).max(-400))
+        };
+        BigNumber::from_parts(scaled(self).hypot(scaled(other)), exponent)
+    }
+    // To the nearest integer, half away from zero. A number with as many integer digits as
+    // the mantissa holds has no fraction left, and one below 0.1 rounds to zero
+    pub fn round(self) -> BigNumber<T> {
+        let digits = -T::epsilon().log10().to_f64().unwrap().floor() as i32;
+        if (self.exponent >= digits) {
+            return self;
+        }
+        if (self.exponent < -1) {
+            return BigNumber::new_d(T::zero());
+        }
+        let shifted = self.signed_base() * from_f64::<T::Wide>(10.0).powi(self.exponent);
+        BigNumber::from_parts(shifted.round(), 0)
+    }
+    pub fn abs(mut self) -> BigNumber<T> {
+        self.is_positive = true;
+        self
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_number::ErrorCause;
+    fn assert_close(actual: BigNumber<f64>, expected: f64, tolerance: f64) {
+        let relative = (actual.to_float() - expected).abs() / expected.abs().max(1e-300);
+        assert!(
+            relative < tolerance,
+            "{} is not {} (relative error {})",
+            actual,
+            expected,
+            relative
+        );
+    }
+    #[test]
+    fn matches_f64_in_range() {
+        for x in [2.0e-30, 0.37, 1.0, 7.5, 42.0, 1.234e17, 6.02e23, 9.99e150] {
+            let number = BigNumber::new_d(x);
+            if (x != 1.0) {
+                assert_close(number.log10(), x.log10(), 1e-15);
+                assert_close(number.ln(), x.ln(), 1e-15);
+            }
+            assert_close(number.sqrt(), x.sqrt(), 1e-15);
+            assert_close(number.cbrt(), x.cbrt(), 1e-15);
+            assert_close(number.powi(2), x.powi(2), 1e-15);
+            assert_close(number.powi(-3), x.powi(-3), 1e-14);
+            assert_close(number.powf(1.5), x.powf(1.5), 1e-13);
+            assert_close(
+                number.hypot(&BigNumber::new_d(x / 3.0)),
+                x.hypot(x / 3.0),
+                1e-15,
+            );
+        }
+        for x in [-20.0, -1.5, 0.5, 3.0, 300.0] {
+            assert_close(BigNumber::new_d(x).exp(), x.exp(), 1e-13);
+        }
+        assert_close(BigNumber::new_d(-27.0).cbrt(), -3.0, 1e-15);
+    }
+    #[test]
+    fn goes_past_f64() {
+        let huge: BigNumber<f64> = "4x10^1000".parse().unwrap();
+        assert_eq!(huge.sqrt().to_string(), "2x10^500");
+        assert_eq!(huge.powi(3).to_string(), "6.4x10^3001");
+        assert_eq!(huge.log10().to_string(), "1.000602059991328K");
+        assert_eq!(BigNumber::<f64>::new_d(10000.0).exp().exponent, 4342);
+        let leg: BigNumber<f64> = "3x10^400".parse().unwrap();
+        let other: BigNumber<f64> = "4x10^400".parse().unwrap();
+        assert_eq!(leg.hypot(&other).to_string(), "5x10^400");
+        let tiny: BigNumber<f64> = "1x10^-4000".parse().unwrap();
+        assert_eq!(leg.hypot(&tiny), leg);
+        let smallest = BigNumber::<f64>::min_positive_value();
+        assert_eq!(smallest.cbrt().exponent, -715_827_883);
+        assert_close(smallest.cbrt().powi(3) / smallest, 1.0, 1e-14);
+    }
+    #[test]
+    fn follows_ieee_outside_the_domain() {
+        let zero = BigNumber::new_d(0.0_f32);
+        let negative = BigNumber::new_d(-4.0_f32);
+        assert!(negative.sqrt().is_nan());
+        assert!(negative.log10().is_nan());
+        assert!(negative.ln().is_nan());
+        assert!(negative.powf(0.5).is_nan());
+        assert_eq!(negative.powf(2.0), BigNumber::new_d(16.0));
+        assert_eq!(zero.log10(), BigNumber::neg_infinity());
+        assert_eq!(zero.ln(), BigNumber::neg_infinity());
+        assert_eq!(zero.powi(-2), BigNumber::infinity());
+        assert_eq!(zero.powf(-0.5), BigNumber::infinity());
+        assert!(zero.powf(f32::NAN).is_nan());
+        assert!(negative.powf(f32::NAN).is_nan());
+        assert_eq!(BigNumber::<f32>::nan().powf(0.0), BigNumber::new_d(1.0));
+        assert!(BigNumber::<f32>::nan().powf(0.5).is_nan());
+        assert_eq!(
+            zero.checked_powi(-1).unwrap_err().cause,
+            ErrorCause::DivisionByZero
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use super::{from_f64, BigNumber, BigNumberError, ErrorCause, Mantissa};
+use num::traits::Bounded;
+// What happens when a result's exponent leaves the i32 range. The operators behave like
+// floats: too large becomes ±inf, too small becomes 0. Every operation also comes as
+//     checked_*     an ExponentOverflow/ExponentUnderflow (or DivisionByZero) error
+//     saturating_*  ±max_value() on overflow, ±1x10^i32::MIN on underflow
+//     wrapping_*    the exponent wrapped around like `i32::wrapping_add`, the mantissa kept
+// NaN and the infinities go through all of them the way they go through the operators.
+//
+// A result before that decision: signed_base x 10^exponent with room in the exponent for
+// anything two i32 exponents can add up to, and the mantissa in the `Mantissa::Wide` type
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Unbounded<W: Mantissa> {
+    signed_base: W,
+    exponent: i64,
+}
+impl<W: Mantissa> Unbounded<W> {
+    pub(super) fn new(signed_base: W, exponent: i64) -> Unbounded<W> {
+        Unbounded {
+            signed_base,
+            exponent,
+        }
+    }
+    // Moves the mantissa into [1, 10), so products of many of them stay finite
+    fn normalized(self) -> Unbounded<W> {
+        if (self.signed_base.is_zero() || !self.signed_base.is_finite()) {
+            return Unbounded::new(self.signed_base, 0);
+        }
+        let shift = self.signed_base.abs().log10().floor();
+        Unbounded::new(
+            self.signed_base / from_f64::<W>(10.0).powf(shift),
+            self.exponent + shift.to_i64().unwrap(),
+        )
+    }
+    pub(super) fn times(self, other: Unbounded<W>) -> Unbounded<W> {
+        Unbounded::new(
+            self.signed_base * other.signed_base,
+            self.exponent + other.exponent,
+        )
+        .normalized()
+    }
+    pub(super) fn reciprocal(self) -> Unbounded<W> {
+        Unbounded::new(W::one() / self.signed_base, -self.exponent).normalized()
+    }
+    // The mantissa as T and the exponent that goes with it; rounding to T can carry into
+    // the next power of ten. Only for finite, non-zero values
+    fn parts<T: Mantissa<Wide = W>>(self) -> (T, i64, bool) {
+        let normalized = self.normalized();
+        let mut base = T::narrow(normalized.signed_base.abs());
+        let mut exponent = normalized.exponent;
+        if (base >= from_f64(10.0)) {
+            base = base / from_f64(10.0);
+            exponent += 1;
+        }
+        (base, exponent, self.signed_base > W::zero())
+    }
+    // The value when its exponent fits, else which end of the range it fell off and its sign
+    fn checked<T: Mantissa<Wide = W>>(self) -> Result<BigNumber<T>, (ErrorCause, bool)> {
+        if (self.signed_base.is_zero()) {
+            return Ok(BigNumber::new_d(T::zero()));
+        }
+        if (self.signed_base.is_nan()) {
+            return Ok(BigNumber::nan());
+        }
+        if (self.signed_base.is_infinite()) {
+            return Ok(BigNumber::special(
+                T::infinity(),
+                self.signed_base > W::zero(),
+            ));
+        }
+        let (base, exponent, is_positive) = self.parts::<T>();
+        match i32::try_from(exponent) {
+            Ok(exponent) => Ok(BigNumber {
+                base,
+                exponent,
+                is_positive,
+            }),
+            Err(_) if (exponent > 0) => Err((ErrorCause::ExponentOverflow, is_positive)),
+            Err(_) => Err((ErrorCause::ExponentUnderflow, is_positive)),
+        }
+    }
+    pub(super) fn to_big_number<T: Mantissa<Wide = W>>(self) -> BigNumber<T> {
+        match self.checked() {
+            Ok(number) => number,
+            Err((ErrorCause::ExponentOverflow, is_positive)) => {
+                BigNumber::special(T::infinity(), is_positive)
+            }
+            Err(_) => BigNumber::new_d(T::zero()),
+        }
+    }
+    // `operation` writes what was computed, only when there is an error to report
+    fn to_checked<T: Mantissa<Wide = W>>(
+        self,
+        operation: impl FnOnce() -> String,
+    ) -> Result<BigNumber<T>, BigNumberError> {
+        self.checked()
+            .map_err(|(cause, _)| BigNumberError::new(cause, &operation(), None))
+    }
+    fn to_saturating<T: Mantissa<Wide = W>>(self) -> BigNumber<T> {
+        let (magnitude, is_positive) = match self.checked() {
+            Ok(number) => return number,
+            Err((ErrorCause::ExponentOverflow, is_positive)) => {
+                (BigNumber::max_value(), is_positive)
+            }
+            Err((_, is_positive)) => (BigNumber::min_positive_value(), is_positive),
+        };
+        match is_positive {
+            true => magnitude,
+            false => -magnitude,
+        }
+    }
+    fn to_wrapping<T: Mantissa<Wide = W>>(self) -> BigNumber<T> {
+        if (self.checked::<T>().is_ok()) {
+            return self.to_big_number();
+        }
+        let (base, exponent, is_positive) = self.parts::<T>();
+        BigNumber {
+            base,
+            exponent: exponent as i32,
+            is_positive,
+        }
+    }
+}
+impl<T: Mantissa> From<BigNumber<T>> for Unbounded<T::Wide> {
+    fn from(number: BigNumber<T>) -> Unbounded<T::Wide> {
+        match number.is_finite() {
+            true => Unbounded::new(number.signed_base(), number.exponent as i64),
+            false => Unbounded::new(number.signed_base(), 0),
+        }
+    }
+}
+impl<T: Mantissa> BigNumber<T> {
+    // The smallest positive value, 1x10^-2147483648
+    pub fn min_positive_value() -> BigNumber<T> {
+        BigNumber {
+            base: T::one(),
+            exponent: i32::MIN,
+            is_positive: true,
+        }
+    }
+    pub fn checked_powi(&self, n: i32) -> Result<BigNumber<T>, BigNumberError> {
+        if (n < 0 && self.is_zero()) {
+            return Err(BigNumberError::new(
+                ErrorCause::DivisionByZero,
+                &format!("{}^{}", self, n),
+                None,
+            ));
+        }
+        self.unbounded_powi(n)
+            .to_checked(|| format!("{}^{}", self, n))
+    }
+    pub fn saturating_powi(&self, n: i32) -> BigNumber<T> {
+        self.unbounded_powi(n).to_saturating()
+    }
+    pub fn wrapping_powi(&self, n: i32) -> BigNumber<T> {
+        self.unbounded_powi(n).to_wrapping()
+    }
+}
+macro_rules! impl_overflow_variants {
+    ($($unbounded:ident, $symbol:literal, $divides:literal, $checked:ident, $saturating:ident, $wrapping:ident);*) => {
+        impl<T: Mantissa> BigNumber<T> {
+            $(
+                pub fn $checked(self, other: BigNumber<T>) -> Result<BigNumber<T>, BigNumberError> {
+                    let operation = || format!("{} {} {}", self, $symbol, other);
+                    if ($divides && other.is_zero()) {
+                        return Err(BigNumberError::new(ErrorCause::DivisionByZero, &operation(), None));
+                    }
+                    self.$unbounded(other).to_checked(operation)
+                }
+                pub fn $saturating(self, other: BigNumber<T>) -> BigNumber<T> {
+                    self.$unbounded(other).to_saturating()
+                }
+                pub fn $wrapping(self, other: BigNumber<T>) -> BigNumber<T> {
+                    self.$unbounded(other).to_wrapping()
+                }
+            )*
+        }
+    };
+}
+impl_overflow_variants!(
+    unbounded_add, "+", false, checked_add, saturating_add, wrapping_add;
+    unbounded_sub, "-", false, checked_sub, saturating_sub, wrapping_sub;
+    unbounded_mul, "*", false, checked_mul, saturating_mul, wrapping_mul;
+    unbounded_div, "/", true, checked_div, saturating_div, wrapping_div;
+    unbounded_rem, "%", true, checked_rem, saturating_rem, wrapping_rem
+);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_number::format_policy::FormatPolicy;
+    use crate::big_number::Notation;
+    fn number(base: f64, exponent: i32) -> BigNumber<f64> {
+        BigNumber::from_parts(base, exponent)
+    }
+    fn cause(result: Result<BigNumber<f64>, BigNumberError>) -> ErrorCause {
+        result.unwrap_err().cause
+    }
+    #[test]
+    fn overflows_past_the_largest_exponent() {
+        let huge = number(5.0, i32::MAX);
+        let max = BigNumber::<f64>::max_value();
+        assert_eq!(huge + huge, BigNumber::infinity());
+        assert_eq!(-huge - huge, BigNumber::neg_infinity());
+        assert_eq!(huge * number(1.0, 1), BigNumber::infinity());
+        assert_eq!(huge / number(-1.0, -1), BigNumber::neg_infinity());
+        assert_eq!(huge.powi(2), BigNumber::infinity());
+        assert_eq!(cause(huge.checked_add(huge)), ErrorCause::ExponentOverflow);
+        assert_eq!(cause(huge.checked_mul(huge)), ErrorCause::ExponentOverflow);
+        assert_eq!(cause(huge.checked_powi(3)), ErrorCause::ExponentOverflow);
+        assert_eq!(huge.saturating_add(huge), max);
+        assert_eq!((-huge).saturating_mul(huge), -max);
+        assert_eq!(huge.saturating_powi(i32::MAX), max);
+        // 1x10^i32::MAX * 10 wraps to 1x10^i32::MIN
+        assert_eq!(
+            number(1.0, i32::MAX).wrapping_mul(number(1.0, 1)),
+            number(1.0, i32::MIN)
+        );
+        assert_eq!(huge.wrapping_add(huge), number(1.0, i32::MIN));
+        // Right at the edge nothing changes
+        assert_eq!(huge.checked_add(number(1.0, 0)), Ok(huge));
+        assert_eq!(
+            number(1.0, i32::MAX - 1).checked_mul(number(1.0, 1)),
+            Ok(number(1.0, i32::MAX))
+        );
+    }
+    #[test]
+    fn underflows_past_the_smallest_exponent() {
+        let tiny = number(2.0, i32::MIN);
+        let min = BigNumber::<f64>::min_positive_value();
+        assert_eq!(tiny * number(1.0, -1), BigNumber::new_d(0.0));
+        assert_eq!(tiny / number(1.0, 1), BigNumber::new_d(0.0));
+        assert_eq!(number(1.0, -2).powi(i32::MAX), BigNumber::new_d(0.0));
+        assert_eq!(number(1.0, 2).powi(i32::MIN), BigNumber::new_d(0.0));
+        assert_eq!(cause(tiny.checked_mul(tiny)), ErrorCause::ExponentUnderflow);
+        assert_eq!(
+            cause(tiny.checked_div(number(1.0, 3))),
+            ErrorCause::ExponentUnderflow
+        );
+        assert_eq!(
+            cause(number(1.0, 2).checked_powi(i32::MIN)),
+            ErrorCause::ExponentUnderflow
+        );
+        assert_eq!(tiny.saturating_mul(number(-1.0, -1)), -min);
+        assert_eq!(tiny.wrapping_mul(number(1.0, -1)), number(2.0, i32::MAX));
+        // Cancellation below the smallest exponent
+        let a = number(1.0000000000000002, i32::MIN);
+        assert_eq!(a - number(1.0, i32::MIN), BigNumber::new_d(0.0));
+        assert_eq!(
+            cause(a.checked_sub(number(1.0, i32::MIN))),
+            ErrorCause::ExponentUnderflow
+        );
+        // Far apart exponents do not overflow the difference between them
+        assert_eq!(tiny + number(3.0, i32::MAX), number(3.0, i32::MAX));
+        assert_eq!(number(3.0, i32::MAX) % tiny, BigNumber::new_d(0.0));
+        assert_eq!(tiny % number(3.0, i32::MAX), tiny);
+    }
+    #[test]
+    fn checks_divisors_and_passes_non_finite_values() {
+        let one = number(1.0, 0);
+        let zero = BigNumber::new_d(0.0);
+        assert_eq!(cause(one.checked_div(zero)), ErrorCause::DivisionByZero);
+        assert_eq!(cause(one.checked_rem(zero)), ErrorCause::DivisionByZero);
+        assert_eq!(cause(zero.checked_powi(-1)), ErrorCause::DivisionByZero);
+        let infinity = BigNumber::<f64>::infinity();
+        assert_eq!(infinity.checked_add(one), Ok(infinity));
+        assert!(infinity.checked_sub(infinity).unwrap().is_nan());
+        assert_eq!(infinity.saturating_mul(one), infinity);
+        assert_eq!(one.wrapping_div(infinity), zero);
+    }
+    #[test]
+    fn writes_the_extreme_exponents() {
+        let edges = [
+            BigNumber::<f64>::max_value(),
+            BigNumber::min_value(),
+            BigNumber::min_positive_value(),
+            number(-1.5, i32::MIN + 2),
+        ];
+        for notation in [
+            Notation::Haven,
+            Notation::Scientific,
+            Notation::Engineering,
+            Notation::SI("m".to_string()),
+        ] {
+            for policy in [
+                FormatPolicy::new(notation.clone()),
+                FormatPolicy::new(notation.clone()).with_significant_digits(3),
+            ] {
+                for edge in edges {
+                    let written = policy.format(&edge);
+                    let read = written.parse::<BigNumber<f64>>();
+                    assert!(read.is_ok(), "{} does not read back", written);
+                }
+            }
+        }
+        assert_eq!(
+            FormatPolicy::new(Notation::Haven).format(&BigNumber::<f64>::min_positive_value()),
+            "1x10^-2147483648"
+        );
+        assert_eq!(
+            FormatPolicy::new(Notation::SI("m".to_string())).format(&number(1.0, i32::MAX)),
+            "10x10^2147483646 m"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+// Randomized checks of every operation against references: f64 where the values fit in it,
+// and the exact BigRational of the written digits everywhere else. Random inputs rarely land
+// on the exact boundaries, so REGRESSIONS below checks those on every run.
+use super::decimal::Decimal;
+use super::format_policy::FormatPolicy;
+use super::{BigNumber, Mantissa, Notation};
+use num::bigint::BigInt;
+use num::rational::BigRational;
+use num::traits::{Pow, Signed, ToPrimitive, Zero};
+use proptest::prelude::*;
+// Results are within a few f64 roundings: the shift to a shared exponent, the operation
+// itself and the renormalization in `from_parts`
+const TOLERANCE: f64 = 1e-14;
+// The same for 34-digit `Decimal` mantissas
+const DECIMAL_TOLERANCE: f64 = 1e-32;
+// Hand-picked (a, b) pairs at the edges of the mantissa/exponent bookkeeping
+const REGRESSIONS: [(&str, &str); 8] = [
+    // Mantissa rounding up to 10 during renormalization
+    ("9.999999999999999", "1x10^-16"),
+    ("-9.999999999999998x10^5", "-2x10^-10"),
+    // Cancellation down to the last digit, and to exactly zero
+    ("1.0000000000000002", "-1"),
+    ("1x10^20", "-9.99999999999999x10^19"),
+    ("9.5", "-9.5"),
+    // Exponents far apart, the smaller operand vanishes
+    ("1x10^400", "1x10^-400"),
+    ("-5x10^2000", "5x10^-2000"),
+    // Products and quotients crossing the f64 exponent range
+    ("2.2250738585072014x10^-308", "1x10^-15"),
+];
+fn number(base: f64, exponent: i32, is_positive: bool) -> BigNumber<f64> {
+    let signed_base = match is_positive {
+        true => base,
+        false => -base,
+    };
+    BigNumber::from_parts(signed_base, exponent)
+}
+// Mantissas anywhere in [1, 10), exponents in `exponents`, and zero now and then
+fn big_number(exponents: std::ops::Range<i32>) -> impl Strategy<Value = BigNumber<f64>> {
+    prop_oneof![
+        1 => Just(BigNumber::new_d(0.0)),
+        20 => (1.0..10.0_f64, exponents, any::<bool>())
+            .prop_map(|(base, exponent, is_positive)| number(base, exponent, is_positive)),
+    ]
+}
+// The value of a finite number's written mantissa and exponent
+fn exact<T: Mantissa>(number: &BigNumber<T>) -> BigRational {
+    if (number.is_zero()) {
+        return BigRational::zero();
+    }
+    let digits = number.base.to_string().replace('.', "");
+    let power = number.exponent as i64 - (digits.len() as i64 - 1);
+    let scale = BigRational::from_integer(BigInt::from(10)).pow(power as i32);
+    let value = BigRational::from_integer(digits.parse().unwrap()) * scale;
+    match number.is_positive {
+        true => value,
+        false => -value,
+    }
+}
+fn to_f64(number: &BigNumber<f64>) -> f64 {
+    number.to_f64().unwrap()
+}
+// |actual - expected| measured against `scale`, which is |expected| unless cancellation makes
+// the operands a better yardstick
+fn assert_close<T: Mantissa>(
+    actual: &BigNumber<T>,
+    expected: &BigRational,
+    scale: &BigRational,
+    tolerance: f64,
+) {
+    if (scale.is_zero()) {
+        assert!(actual.is_zero(), "{} is not 0", actual);
+        return;
+    }
+    let error = ((exact(actual) - expected).abs() / scale).to_f64().unwrap();
+    assert!(
+        error <= tolerance,
+        "{} is off by {} relative to the exact value",
+        actual,
+        error
+    );
+}
+fn check_operations<T: Mantissa>(a: BigNumber<T>, b: BigNumber<T>, tolerance: f64) {
+    let (exact_a, exact_b) = (exact(&a), exact(&b));
+    let magnitudes = exact_a.abs() + exact_b.abs();
+    assert_close(&(a + b), &(&exact_a + &exact_b), &magnitudes, tolerance);
+    assert_close(&(a - b), &(&exact_a - &exact_b), &magnitudes, tolerance);
+    let product = &exact_a * &exact_b;
+    assert_close(&(a * b), &product, &product.abs(), tolerance);
+    if (!b.is_zero()) {
+        let quotient = &exact_a / &exact_b;
+        assert_close(&(a / b), &quotient, &quotient.abs(), tolerance);
+    }
+}
+proptest! {
+    #[test]
+    fn matches_f64_where_it_fits(a in big_number(-100..100), b in big_number(-100..100)) {
+        let (x, y) = (to_f64(&a), to_f64(&b));
+        let close = |actual: BigNumber<f64>, expected: f64, scale: f64| {
+            (to_f64(&actual) - expected).abs() <= TOLERANCE * scale
+        };
+        prop_assert!(close(a + b, x + y, x.abs() + y.abs()));
+        prop_assert!(close(a - b, x - y, x.abs() + y.abs()));
+        prop_assert!(close(a * b, x * y, (x * y).abs()));
+        if (!b.is_zero()) {
+            prop_assert!(close(a / b, x / y, (x / y).abs()));
+            let remainder = to_f64(&(a % b));
+            prop_assert!(remainder.abs() < y.abs() || y.abs() < x.abs() * TOLERANCE);
+            prop_assert!(remainder == 0.0 || remainder.is_sign_negative() == x.is_sign_negative());
+        }
+        prop_assert_eq!(a < b, x < y);
+        prop_assert_eq!(a == b, x == y);
+    }
+    #[test]
+    fn matches_exact_arithmetic_past_f64(
+        a in big_number(-1000..1000),
+        b in big_number(-1000..1000),
+    ) {
+        check_operations(a, b, TOLERANCE);
+    }
+    #[test]
+    fn keeps_decimal_mantissas_to_their_digits(
+        a in big_number(-1000..1000),
+        b in big_number(-1000..1000),
+    ) {
+        check_operations(a.cast::<Decimal>(), b.cast::<Decimal>(), DECIMAL_TOLERANCE);
+    }
+    #[test]
+    fn is_commutative(a in big_number(-1000..1000), b in big_number(-1000..1000)) {
+        prop_assert_eq!(a + b, b + a);
+        prop_assert_eq!(a * b, b * a);
+    }
+    #[test]
+    fn is_associative_within_tolerance(
+        a in big_number(-300..300),
+        b in big_number(-300..300),
+        c in big_number(-300..300),
+    ) {
+        let magnitudes = exact(&a).abs() + exact(&b).abs() + exact(&c).abs();
+        assert_close(&((a + b) + c), &exact(&(a + (b + c))), &magnitudes, TOLERANCE);
+        let product = exact(&(a * (b * c)));
+        assert_close(&((a * b) * c), &product, &product.abs(), TOLERANCE);
+    }
+    #[test]
+    fn keeps_identities_and_signs(a in big_number(-1000..1000), b in big_number(-1000..1000)) {
+        let zero = BigNumber::new_d(0.0);
+        let one = BigNumber::new_d(1.0);
+        prop_assert_eq!(a + zero, a);
+        prop_assert_eq!(a * one, a);
+        prop_assert_eq!(a - a, zero);
+        prop_assert_eq!(-(-a), a);
+        prop_assert_eq!(a * zero, zero);
+        if (!a.is_zero()) {
+            prop_assert_eq!(a / a, one);
+        }
+        prop_assert_eq!((a * b).signum(), a.signum() * b.signum());
+        prop_assert_eq!((-a).signum(), -a.signum());
+        let sum = exact(&a) + exact(&b);
+        if (!sum.is_zero() && !(a + b).is_zero()) {
+            prop_assert_eq!((a + b).is_positive, sum.is_positive());
+        }
+    }
+    #[test]
+    fn round_trips_through_every_notation(
+        a in big_number(-2000..2000),
+        notation in prop_oneof![
+            Just(Notation::Haven),
+            Just(Notation::Scientific),
+            Just(Notation::Engineering),
+            Just(Notation::SI("m".to_string())),
+        ],
+    ) {
+        let written = FormatPolicy::new(notation).format(&a);
+        prop_assert_eq!(written.parse::<BigNumber<f64>>().unwrap(), a, "{}", written);
+        let narrow: BigNumber = a.cast();
+        prop_assert_eq!(narrow.to_string().parse::<BigNumber>().unwrap(), narrow);
+    }
+}
+#[test]
+fn regressions() {
+    for (a, b) in REGRESSIONS {
+        let (a, b): (BigNumber<f64>, BigNumber<f64>) = (a.parse().unwrap(), b.parse().unwrap());
+        check_operations(a, b, TOLERANCE);
+        check_operations(b, a, TOLERANCE);
+        assert_eq!(a + b, b + a);
+        assert_eq!(a * b, b * a);
+    }
+}
@@ -0,0 +1,221 @@
+use super::overflow::Unbounded;
+use super::{from_f64, BigNumber, BigVec2, BigVec3, Mantissa};
+use num::traits::{Float, One, ToPrimitive, Zero};
+use std::iter::{Product, Sum};
+// Past this magnitude the running wide values are moved to a larger power of ten
+const RESCALE_LIMIT: f64 = 1e200;
+// Neumaier's variant of Kahan summation, kept as wide mantissas (f64 for the binary floats)
+// at a shared power of ten so no BigNumber is built until the end: the error stays around
+// one ulp of the total however many terms are added, instead of growing with the count.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CompensatedSum<W: Mantissa = f64> {
+    sum: W,
+    compensation: W,
+    // Wider than a BigNumber exponent, so it can go past either end before the total
+    exponent: i64,
+    // NaN and the infinities, summed as plain floats
+    special: W,
+}
+impl<W: Mantissa> CompensatedSum<W> {
+    pub fn new() -> CompensatedSum<W> {
+        CompensatedSum::default()
+    }
+    fn rescale(&mut self, exponent: i64) {
+        let factor = from_f64::<W>(10.0).powi((self.exponent - exponent).max(-400) as i32);
+        self.sum = self.sum * factor;
+        self.compensation = self.compensation * factor;
+        self.exponent = exponent;
+    }
+    pub fn add<T: Mantissa<Wide = W>>(&mut self, number: &BigNumber<T>) {
+        if (!number.is_finite()) {
+            self.special = self.special + number.signed_base();
+            return;
+        }
+        if (number.is_zero()) {
+            return;
+        }
+        let exponent = number.exponent as i64;
+        if (self.sum.is_zero() && self.compensation.is_zero()) {
+            self.exponent = exponent;
+        }
+        if (exponent > self.exponent) {
+            self.rescale(exponent);
+        }
+        let scale = from_f64::<W>(10.0).powi((exponent - self.exponent).max(-400) as i32);
+        let term = number.signed_base() * scale;
+        let total = self.sum + term;
+        self.compensation = self.compensation
+            + match (self.sum.abs() >= term.abs()) {
+                true => (self.sum - total) + term,
+                false => (term - total) + self.sum,
+            };
+        self.sum = total;
+        if (self.sum.abs() > from_f64(RESCALE_LIMIT)) {
+            self.rescale(self.exponent + 200);
+        }
+    }
+    pub fn total<T: Mantissa<Wide = W>>(&self) -> BigNumber<T> {
+        match (self.special.is_zero()) {
+            true => Unbounded::new(self.sum + self.compensation, self.exponent).to_big_number(),
+            false => BigNumber::from_parts(self.special, 0),
+        }
+    }
+}
+impl<T: Mantissa> Sum for BigNumber<T> {
+    fn sum<I: Iterator<Item = BigNumber<T>>>(iter: I) -> BigNumber<T> {
+        iter.fold(CompensatedSum::new(), |mut sum, number| {
+            sum.add(&number);
+            sum
+        })
+        .total()
+    }
+}
+impl<'a, T: Mantissa> Sum<&'a BigNumber<T>> for BigNumber<T> {
+    fn sum<I: Iterator<Item = &'a BigNumber<T>>>(iter: I) -> BigNumber<T> {
+        iter.fold(CompensatedSum::new(), |mut sum, number| {
+            sum.add(number);
+            sum
+        })
+        .total()
+    }
+}
+// Mantissas are multiplied as wide values and only folded into the exponent when they leave
+// [1e-200, 1e200], so the error is one rounding per factor. Once the product is zero or
+// not finite it has no power of ten left to fold
+fn product<T: Mantissa>(iter: impl Iterator<Item = BigNumber<T>>) -> BigNumber<T> {
+    let limit = from_f64::<T::Wide>(RESCALE_LIMIT);
+    let mut mantissa = T::Wide::one();
+    let mut exponent = 0_i64;
+    for number in iter {
+        if (!number.is_finite() || number.is_zero()) {
+            mantissa = mantissa * number.signed_base();
+            continue;
+        }
+        mantissa = mantissa * number.signed_base();
+        exponent += number.exponent as i64;
+        if (mantissa.is_zero() || !mantissa.is_finite()) {
+            continue;
+        }
+        if (mantissa.abs() > limit || mantissa.abs() < limit.recip()) {
+            let shift = mantissa.abs().log10().floor();
+            mantissa = mantissa / from_f64::<T::Wide>(10.0).powf(shift);
+            exponent += shift.to_i64().unwrap();
+        }
+    }
+    if (!mantissa.is_finite() || mantissa.is_zero()) {
+        return BigNumber::from_parts(mantissa, 0);
+    }
+    Unbounded::new(mantissa, exponent).to_big_number()
+}
+impl<T: Mantissa> Product for BigNumber<T> {
+    fn product<I: Iterator<Item = BigNumber<T>>>(iter: I) -> BigNumber<T> {
+        product(iter)
+    }
+}
+impl<'a, T: Mantissa> Product<&'a BigNumber<T>> for BigNumber<T> {
+    fn product<I: Iterator<Item = &'a BigNumber<T>>>(iter: I) -> BigNumber<T> {
+        product(iter.copied())
+    }
+}
+// Vectors are summed one compensated sum per component
+macro_rules! impl_big_vector_sum {
+    ($vector:ident { $($component:ident),* }) => {
+        impl<T: Mantissa> Sum for $vector<T> {
+            fn sum<I: Iterator<Item = $vector<T>>>(iter: I) -> $vector<T> {
+                $(let mut $component = CompensatedSum::new();)*
+                for vector in iter {
+                    $($component.add(&vector.$component);)*
+                }
+                $vector { $($component: $component.total()),* }
+            }
+        }
+        impl<'a, T: Mantissa> Sum<&'a $vector<T>> for $vector<T> {
+            fn sum<I: Iterator<Item = &'a $vector<T>>>(iter: I) -> $vector<T> {
+                iter.copied().sum()
+            }
+        }
+    };
+}
+impl_big_vector_sum!(BigVec2 { x, y });
+impl_big_vector_sum!(BigVec3 { x, y, z });
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn sums_without_accumulating_error() {
+        let tenth = BigNumber::new_d(0.1_f64);
+        let total: BigNumber<f64> = std::iter::repeat_n(&tenth, 100_000).sum();
+        assert_eq!(total, BigNumber::new_d(10_000.0));
+        let cancelling: BigNumber<f64> = ["1e20", "1", "-1e20", "1e-5"]
+            .iter()
+            .map(|written| written.parse::<BigNumber<f64>>().unwrap())
+            .sum();
+        assert_eq!(cancelling, BigNumber::new_d(1.00001));
+        let huge: BigNumber = ["1x10^5000", "2x10^5000", "-1x10^4990"]
+            .iter()
+            .map(|written| written.parse::<BigNumber>().unwrap())
+            .sum();
+        assert_eq!(huge.to_string(), "3x10^5000");
+        let empty: BigNumber = std::iter::empty::<BigNumber>().sum();
+        assert!(empty.is_zero());
+        let with_infinity: BigNumber = [BigNumber::new_d(1.0), BigNumber::infinity()]
+            .into_iter()
+            .sum();
+        assert_eq!(with_infinity, BigNumber::infinity());
+    }
+    #[test]
+    fn multiplies_past_f64() {
+        let factors = [BigNumber::<f64>::new_d(1e300); 10];
+        let total: BigNumber<f64> = factors.iter().product();
+        assert_eq!(total.to_string(), "1x10^3000");
+        let factorial: BigNumber<f64> = (1..=20).map(|n| BigNumber::new_d(n as f64)).product();
+        assert_eq!(factorial, BigNumber::new_d(2432902008176640000.0));
+        let with_zero: BigNumber = [BigNumber::new_d(5.0), BigNumber::new_d(0.0)]
+            .iter()
+            .product();
+        assert!(with_zero.is_zero());
+        let zero_first: BigNumber = [BigNumber::new_d(0.0), BigNumber::new_d(5.0)]
+            .into_iter()
+            .product();
+        assert!(zero_first.is_zero());
+        let infinite: BigNumber = [BigNumber::infinity(), BigNumber::new_d(2.0)]
+            .into_iter()
+            .product();
+        assert_eq!(infinite, BigNumber::infinity());
+        let not_a_number: BigNumber = [BigNumber::nan(), BigNumber::new_d(3.0)]
+            .into_iter()
+            .product();
+        assert!(not_a_number.is_nan());
+    }
+    #[test]
+    fn saturates_at_the_exponent_limits() {
+        let power = |exponent: i32| BigNumber::<f64> {
+            base: 1.0,
+            exponent,
+            is_positive: true,
+        };
+        let largest: BigNumber<f64> = [power(i32::MAX), power(i32::MIN)].iter().sum();
+        assert_eq!(largest, power(i32::MAX));
+        let smallest: BigNumber<f64> = [power(i32::MIN), power(i32::MIN)].iter().sum();
+        assert_eq!(smallest.exponent, i32::MIN);
+        let overflowing: BigNumber<f64> = [power(i32::MAX) * 9.0; 3].iter().sum();
+        assert_eq!(overflowing, BigNumber::infinity());
+        let huge: BigNumber<f64> = [power(2_000_000_000); 3].iter().product();
+        assert_eq!(huge, BigNumber::infinity());
+        let negative: BigNumber<f64> = [power(2_000_000_000), -power(2_000_000_000)]
+            .iter()
+            .product();
+        assert_eq!(negative, -BigNumber::<f64>::infinity());
+        let tiny: BigNumber<f64> = [power(-2_000_000_000); 3].iter().product();
+        assert!(tiny.is_zero());
+    }
+    #[test]
+    fn sums_vectors() {
+        let step = BigVec2::new(BigNumber::new_d(0.1_f64), BigNumber::new_d(-0.2));
+        let total: BigVec2<f64> = vec![step; 1000].into_iter().sum();
+        assert_eq!(
+            total,
+            BigVec2::new(BigNumber::new_d(100.0), BigNumber::new_d(-200.0))
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use super::{sign_from_bool, BigNumber, BigVec2, Mantissa};
+use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+// The structured form, {"base": 4.2, "exponent": 15, "sign": -1}, is what a plain
+// `#[derive(Serialize)]` field gets. Use `as_string` for the written form.
+#[derive(Deserialize)]
+#[serde(rename = "BigNumber", deny_unknown_fields)]
+struct Structured<T> {
+    base: T,
+    exponent: i32,
+    sign: i32,
+}
+impl<T: Mantissa + Serialize> Serialize for BigNumber<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BigNumber", 3)?;
+        state.serialize_field("base", &self.base)?;
+        state.serialize_field("exponent", &self.exponent)?;
+        state.serialize_field("sign", &sign_from_bool(self.is_positive))?;
+        state.end()
+    }
+}
+impl<'de, T: Mantissa + Deserialize<'de>> Deserialize<'de> for BigNumber<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let structured = Structured::<T>::deserialize(deserializer)?;
+        let is_normalized = structured.base.is_zero()
+            || !structured.base.is_finite()
+            || (structured.base >= T::one() && structured.base < super::from_f64(10.0));
+        if (!is_normalized) {
+            return Err(de::Error::custom(format!(
+                "base {} is outside [1, 10)",
+                structured.base
+            )));
+        }
+        if (structured.sign != 1 && structured.sign != -1) {
+            return Err(de::Error::custom(format!(
+                "sign {} is neither 1 nor -1",
+                structured.sign
+            )));
+        }
+        if (structured.base.is_zero() || !structured.base.is_finite()) {
+            return Ok(BigNumber::new_d(match structured.sign {
+                1 => structured.base,
+                _ => -structured.base,
+            }));
+        }
+        Ok(BigNumber {
+            base: structured.base,
+            exponent: structured.exponent,
+            is_positive: (structured.sign == 1),
+        })
+    }
+}
+impl<T: Mantissa + Serialize> Serialize for BigVec2<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("BigVec2", 2)?;
+        state.serialize_field("x", &self.x)?;
+        state.serialize_field("y", &self.y)?;
+        state.end()
+    }
+}
+impl<'de, T: Mantissa + Deserialize<'de>> Deserialize<'de> for BigVec2<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "BigVec2", deny_unknown_fields)]
+        struct Components<T: Mantissa> {
+            x: BigNumber<T>,
+            y: BigNumber<T>,
+        }
+        let components = Components::<T>::deserialize(deserializer)?;
+        Ok(BigVec2 {
+            x: components.x,
+            y: components.y,
+        })
+    }
+}
+// `#[serde(with = "big_number::serialization::as_string")]` writes a `BigNumber` as
+// "4.2QD" or "1.3x10^27", and a `BigVec2` as a pair of those strings
+pub mod as_string {
+    use super::super::{BigNumber, BigVec2, Mantissa};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    pub trait Written: Sized {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+    }
+    impl<T: Mantissa> Written for BigNumber<T> {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            String::deserialize(deserializer)?
+                .parse::<BigNumber<T>>()
+                .map_err(de::Error::custom)
+        }
+    }
+    impl<T: Mantissa> Written for BigVec2<T> {
+        fn write<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            (self.x.to_string(), self.y.to_string()).serialize(serializer)
+        }
+        fn read<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let (x, y) = <(String, String)>::deserialize(deserializer)?;
+            Ok(BigVec2 {
+                x: x.parse().map_err(de::Error::custom)?,
+                y: y.parse().map_err(de::Error::custom)?,
+            })
+        }
+    }
+    pub fn serialize<V: Written, S: Serializer>(
+        value: &V,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.write(serializer)
+    }
+    pub fn deserialize<'de, V: Written, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<V, D::Error> {
+        V::read(deserializer)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::super::Format;
+    use super::*;
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Snapshot {
+        mass: BigNumber,
+        #[serde(with = "as_string")]
+        distance: BigNumber<f64>,
+        position: BigVec2,
+        #[serde(with = "as_string")]
+        velocity: BigVec2,
+    }
+    fn snapshot() -> Snapshot {
+        Snapshot {
+            mass: "-4.2QD".parse().unwrap(),
+            distance: "1.3x10^27".parse().unwrap(),
+            position: BigVec2 {
+                x: "16.666666B".parse().unwrap(),
+                y: "0.0".parse().unwrap(),
+            },
+            velocity: BigVec2 {
+                x: "3.2x10^-5".parse().unwrap(),
+                y: "-999.5SP".parse().unwrap(),
+            },
+        }
+    }
+    #[test]
+    fn writes_both_forms() {
+        let written = serde_json::to_string(&snapshot()).unwrap();
+        assert_eq!(
+            written,
+            concat!(
+                r#"{"mass":{"base":4.2,"exponent":15,"sign":-1},"distance":"1.3x10^27","#,
+                r#""position":{"x":{"base":1.6666666,"exponent":10,"sign":1},"#,
+                r#""y":{"base":0.0,"exponent":0,"sign":1}},"#,
+                r#""velocity":["3.2x10^-5","-999.5SP"]}"#
+            )
+        );
+    }
+    #[test]
+    fn round_trips_losslessly() {
+        let original = snapshot();
+        let read: Snapshot =
+            serde_json::from_str(&serde_json::to_string(&original).unwrap()).unwrap();
+        assert_eq!(read, original);
+        assert_eq!(read.mass.to_string(), "-4.2QD");
+        let mut number = BigNumber::new_d(1.0_f32);
+        for _ in 0..100 {
+            number = number * 7.3 - 2.9;
+            let structured: BigNumber =
+                serde_json::from_str(&serde_json::to_string(&number).unwrap()).unwrap();
+            assert_eq!(structured, number);
+        }
+    }
+    #[test]
+    fn rejects_unnormalized_numbers() {
+        for written in [
+            r#"{"base":12.0,"exponent":3,"sign":1}"#,
+            r#"{"base":1.5,"exponent":3,"sign":0}"#,
+            r#"{"base":1.5,"exponent":3}"#,
+        ] {
+            assert!(
+                serde_json::from_str::<BigNumber>(written).is_err(),
+                "{}",
+                written
+            );
+        }
+        let format: Format = serde_json::from_str(r#"{"Haven":"4.2QD"}"#).unwrap();
+        assert_eq!(BigNumber::<f32>::new(format).unwrap().to_string(), "4.2QD");
+    }
+}
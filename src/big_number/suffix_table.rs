@@ -0,0 +1,202 @@
+use super::HAVEN_ABBREVIATIONS;
+use std::{cell::RefCell, rc::Rc};
+const UNIT_PREFIXES: [&str; 10] = ["", "U", "D", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No"];
+const TEN_PREFIXES: [&str; 9] = ["Dc", "Vg", "Tg", "Qag", "Qig", "Sxg", "Spg", "Og", "Ng"];
+const LONG_SCALE_PREFIXES: [&str; 10] = [
+    "m", "b", "tr", "quadr", "quint", "sext", "sept", "oct", "non", "dec",
+];
+// Suffixes used by Haven numbers, one per power of a thousand: entry `i` stands for 10^(3i)
+// and entry 0 is written without a suffix. Numbers past the last entry fall back to Scientific.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuffixTable {
+    suffixes: Vec<String>,
+    // Written between the number and its suffix, e.g. the space in "1.5 milliard"
+    separator: String,
+}
+impl SuffixTable {
+    pub fn new(suffixes: &[&str]) -> SuffixTable {
+        for (index, suffix) in suffixes.iter().enumerate().skip(1) {
+            if (suffix.is_empty()
+                || suffix.starts_with(|char: char| char.is_ascii_digit() || "+-.".contains(char)))
+            {
+                panic!(
+                    "suffix {:?} for 10^{} cannot be told apart from the number",
+                    suffix,
+                    index * 3
+                );
+            }
+            if (suffixes[..index].contains(suffix)) {
+                panic!("suffix {:?} appears more than once", suffix);
+            }
+        }
+        SuffixTable {
+            suffixes: std::iter::once("")
+                .chain(suffixes.iter().skip(1).copied())
+                .map(str::to_string)
+                .collect(),
+            separator: String::new(),
+        }
+    }
+    pub fn with_separator(mut self, separator: &str) -> SuffixTable {
+        self.separator = separator.to_string();
+        self
+    }
+    // K, M, B, T, QD, QN, SX, SP: the table Haven numbers have always used
+    pub fn short_scale() -> SuffixTable {
+        let suffixes = HAVEN_ABBREVIATIONS
+            .iter()
+            .map(|suffix| suffix.unwrap_or(""))
+            .collect::<Vec<&str>>();
+        SuffixTable::new(&suffixes)
+    }
+    // The short scale continued with Oc, No, Dc, UDc, ... Vg, ... NoNg up to Ce, the centillion (10^303)
+    pub fn extended_short_scale() -> SuffixTable {
+        let mut suffixes = SuffixTable::short_scale().suffixes;
+        for illion in (HAVEN_ABBREVIATIONS.len() - 1)..100 {
+            suffixes.push(match illion {
+                0..=9 => UNIT_PREFIXES[illion].to_string(),
+                _ => format!(
+                    "{}{}",
+                    UNIT_PREFIXES[illion % 10],
+                    TEN_PREFIXES[illion / 10 - 1]
+                ),
+            });
+        }
+        suffixes.push("Ce".to_string());
+        SuffixTable {
+            suffixes,
+            separator: String::new(),
+        }
+    }
+    // thousand, million, milliard, billion, billiard, ... up to decilliard (10^63)
+    pub fn long_scale() -> SuffixTable {
+        let mut suffixes = vec![String::new(), "thousand".to_string()];
+        for prefix in LONG_SCALE_PREFIXES {
+            suffixes.push(format!("{}illion", prefix));
+            suffixes.push(format!("{}illiard", prefix));
+        }
+        SuffixTable {
+            suffixes,
+            separator: " ".to_string(),
+        }
+    }
+    // One past the largest exponent this table can write
+    pub fn exponent_limit(&self) -> i32 {
+        (self.suffixes.len() * 3) as i32
+    }
+    pub fn suffix(&self, exponent: i32) -> Option<&str> {
+        match (0..self.exponent_limit()).contains(&exponent) {
+            true => Some(&self.suffixes[(exponent / 3) as usize]),
+            false => None,
+        }
+    }
+    pub fn separator(&self) -> &str {
+        &self.separator
+    }
+    // The exponent a written suffix stands for
+    pub fn exponent_of(&self, suffix: &str) -> Option<i32> {
+        self.suffixes
+            .iter()
+            .position(|candidate| candidate == suffix)
+            .map(|position| position as i32 * 3)
+    }
+    // Strips the longest suffix `x` ends with, as long as it is not the tail of a longer word
+    pub(super) fn split_suffix<'a>(&self, x: &'a str) -> Option<(&'a str, i32)> {
+        self.suffixes
+            .iter()
+            .enumerate()
+            .filter(|(_, suffix)| !suffix.is_empty())
+            .filter_map(|(index, suffix)| Some((x.strip_suffix(suffix.as_str())?, index)))
+            .filter(|(number, _)| !number.ends_with(char::is_alphabetic))
+            .min_by_key(|(number, _)| number.len())
+            .map(|(number, index)| (number, index as i32 * 3))
+    }
+}
+impl Default for SuffixTable {
+    fn default() -> SuffixTable {
+        SuffixTable::short_scale()
+    }
+}
+thread_local! {
+    static ACTIVE_TABLE: RefCell<Rc<SuffixTable>> = RefCell::new(Rc::new(SuffixTable::default()));
+}
+// Makes `table` the one every Haven number on this thread is parsed and written with,
+// returning the table it replaces
+pub fn set_suffix_table(table: SuffixTable) -> SuffixTable {
+    ACTIVE_TABLE.with(|active| {
+        let previous = active.replace(Rc::new(table));
+        Rc::try_unwrap(previous).unwrap_or_else(|shared| (*shared).clone())
+    })
+}
+pub fn with_suffix_table<R>(table: SuffixTable, f: impl FnOnce() -> R) -> R {
+    let previous = set_suffix_table(table);
+    let result = f();
+    set_suffix_table(previous);
+    result
+}
+pub(super) fn active_table() -> Rc<SuffixTable> {
+    ACTIVE_TABLE.with(|active| active.borrow().clone())
+}
+#[cfg(test)]
+mod tests {
+    use super::super::BigNumber;
+    use super::*;
+    #[test]
+    fn extended_table_reaches_centillion() {
+        let table = SuffixTable::extended_short_scale();
+        assert_eq!(table.suffix(26), Some("SP"));
+        assert_eq!(table.suffix(27), Some("Oc"));
+        assert_eq!(table.suffix(33), Some("Dc"));
+        assert_eq!(table.suffix(36), Some("UDc"));
+        assert_eq!(table.suffix(63), Some("Vg"));
+        assert_eq!(table.suffix(300), Some("NoNg"));
+        assert_eq!(table.suffix(303), Some("Ce"));
+        assert_eq!(table.exponent_limit(), 306);
+        with_suffix_table(table, || {
+            for exponent in 0..306 {
+                let mut number = BigNumber::new_d(4.2_f32);
+                number.increase_power(exponent).unwrap();
+                let written = number.to_string();
+                assert!(!written.contains("x10^"), "{}", written);
+                assert_eq!(written.parse::<BigNumber>().unwrap(), number, "{}", written);
+            }
+            assert_eq!(
+                "4.2x10^306".parse::<BigNumber>().unwrap().to_string(),
+                "4.2x10^306"
+            );
+            assert_eq!("1.5Oc".parse::<BigNumber>().unwrap().exponent, 27);
+        });
+        assert!("1.5Oc".parse::<BigNumber>().is_err());
+    }
+    #[test]
+    fn long_scale_writes_words() {
+        with_suffix_table(SuffixTable::long_scale(), || {
+            let milliard = "1.5 milliard".parse::<BigNumber>().unwrap();
+            assert_eq!(milliard.exponent, 9);
+            assert_eq!(milliard.to_string(), "1.5 milliard");
+            let billiard: BigNumber = "2.5x10^16".parse::<BigNumber>().unwrap() * 1.0;
+            assert_eq!(billiard.to_string(), "25 billiard");
+            assert_eq!(
+                "-7 thousand".parse::<BigNumber>().unwrap().to_string(),
+                "-7 thousand"
+            );
+        });
+    }
+    #[test]
+    fn custom_tables_replace_the_default() {
+        let table = SuffixTable::new(&["", "k", "Mio", "Mrd", "Bio", "Brd"]).with_separator(" ");
+        let previous = set_suffix_table(table);
+        assert_eq!(previous, SuffixTable::short_scale());
+        let number = "12.5 Mrd".parse::<BigNumber>().unwrap();
+        assert_eq!(number.exponent, 10);
+        assert_eq!((number * 1000.0).to_string(), "12.5 Bio");
+        assert!("4.2QD".parse::<BigNumber>().is_err());
+        set_suffix_table(previous);
+        assert_eq!("4.2QD".parse::<BigNumber>().unwrap().exponent, 15);
+    }
+    #[test]
+    #[should_panic]
+    fn rejects_ambiguous_suffixes() {
+        SuffixTable::new(&["", "K", "K"]);
+    }
+}
@@ -0,0 +1,294 @@
+use super::{
+    from_f64, shift_decimal_point, split_sign, BigNumber, BigNumberError, ErrorCause, Mantissa,
+};
+use num::traits::{Bounded, Float, FromPrimitive, Num, One, Signed, ToPrimitive, Zero};
+// num-traits implementations, so a BigNumber fits generic numeric code
+impl<T: Mantissa> Zero for BigNumber<T> {
+    fn zero() -> BigNumber<T> {
+        BigNumber::new_d(T::zero())
+    }
+    fn is_zero(&self) -> bool {
+        BigNumber::is_zero(self)
+    }
+}
+impl<T: Mantissa> One for BigNumber<T> {
+    fn one() -> BigNumber<T> {
+        BigNumber::new_d(T::one())
+    }
+}
+impl<T: Mantissa> Num for BigNumber<T> {
+    type FromStrRadixErr = BigNumberError;
+    // Radix 10 reads everything `FromStr` does, other radixes plain "-ff.8" style numbers
+    fn from_str_radix(x: &str, radix: u32) -> Result<BigNumber<T>, BigNumberError> {
+        if (radix == 10) {
+            return x.parse();
+        }
+        assert!(
+            (2..=36).contains(&radix),
+            "from_str_radix: radix must lie in the range `[2, 36]`"
+        );
+        let (is_positive, unsigned, start) = split_sign(x);
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if (integer.is_empty() && fraction.is_empty()) {
+            return Err(BigNumberError::new(ErrorCause::BadPrefix, x, Some(start)).within(x, 0));
+        }
+        let digit = |char: char, index: usize| {
+            char.to_digit(radix)
+                .map(|digit| BigNumber::new_d(digit as f64))
+                .ok_or_else(|| {
+                    BigNumberError::new(ErrorCause::BadPrefix, x, Some(start + index)).within(x, 0)
+                })
+        };
+        // Summed with an f64 mantissa and rounded to `T` once at the end
+        let radix_number = BigNumber::new_d(radix as f64);
+        let mut value = BigNumber::<f64>::zero();
+        for (index, char) in integer.char_indices() {
+            value = value * radix_number + digit(char, index)?;
+        }
+        let mut place = BigNumber::one();
+        for (index, char) in fraction.char_indices() {
+            place /= radix_number;
+            value += place * digit(char, integer.len() + 1 + index)?;
+        }
+        Ok(match is_positive {
+            true => value.cast(),
+            false => -value.cast(),
+        })
+    }
+}
+impl<T: Mantissa> Signed for BigNumber<T> {
+    fn abs(&self) -> BigNumber<T> {
+        BigNumber::abs(*self)
+    }
+    fn abs_sub(&self, other: &BigNumber<T>) -> BigNumber<T> {
+        match (self <= other) {
+            true => BigNumber::zero(),
+            false => *self - *other,
+        }
+    }
+    fn signum(&self) -> BigNumber<T> {
+        match (self.is_zero() || self.is_nan()) {
+            true => *self,
+            false => BigNumber::new_d(T::narrow(Float::signum(self.signed_base()))),
+        }
+    }
+    fn is_positive(&self) -> bool {
+        self.is_positive && !self.is_zero() && !self.is_nan()
+    }
+    fn is_negative(&self) -> bool {
+        !self.is_positive && !self.is_zero() && !self.is_nan()
+    }
+}
+// The largest finite values, 9.99..x10^2147483647 and its negative
+impl<T: Mantissa> Bounded for BigNumber<T> {
+    fn min_value() -> BigNumber<T> {
+        -BigNumber::<T>::max_value()
+    }
+    fn max_value() -> BigNumber<T> {
+        let ten: T = from_f64(10.0);
+        BigNumber::special(ten - ten * T::epsilon(), true).with_exponent(i32::MAX)
+    }
+}
+impl<T: Mantissa> BigNumber<T> {
+    fn with_exponent(mut self, exponent: i32) -> BigNumber<T> {
+        self.exponent = exponent;
+        self
+    }
+    // The integer part, when it fits an i128
+    fn truncate_to_i128(&self) -> Option<i128> {
+        if (!self.is_finite()) {
+            return None;
+        }
+        if (self.is_zero() || self.exponent < 0) {
+            return Some(0);
+        }
+        let places = usize::try_from(self.exponent)
+            .ok()
+            .filter(|places| *places < 40)?;
+        let shifted = shift_decimal_point(&self.base.to_string(), places);
+        let magnitude = shifted.split('.').next()?.parse::<i128>().ok()?;
+        Some(match self.is_positive {
+            true => magnitude,
+            false => -magnitude,
+        })
+    }
+}
+impl<T: Mantissa> ToPrimitive for BigNumber<T> {
+    fn to_i64(&self) -> Option<i64> {
+        self.truncate_to_i128()?.try_into().ok()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.truncate_to_i128()?.try_into().ok()
+    }
+    fn to_i128(&self) -> Option<i128> {
+        self.truncate_to_i128()
+    }
+    fn to_u128(&self) -> Option<u128> {
+        match (self.is_positive || self.is_zero()) {
+            true => self.truncate_to_i128()?.try_into().ok(),
+            false => None,
+        }
+    }
+    // Read back from the written digits, so it is the f64 nearest to the mantissa's
+    // decimal value. Too large values become infinite, too small ones zero.
+    fn to_f64(&self) -> Option<f64> {
+        if (!self.is_finite()) {
+            return self.signed_base().to_f64();
+        }
+        let magnitude = format!("{}e{}", self.base, self.exponent)
+            .parse::<f64>()
+            .ok()?;
+        Some(match self.is_positive {
+            true => magnitude,
+            false => -magnitude,
+        })
+    }
+}
+impl<T: Mantissa> FromPrimitive for BigNumber<T> {
+    fn from_i64(n: i64) -> Option<BigNumber<T>> {
+        Some(BigNumber::from(n))
+    }
+    fn from_u64(n: u64) -> Option<BigNumber<T>> {
+        Some(BigNumber::from(n))
+    }
+    fn from_i128(n: i128) -> Option<BigNumber<T>> {
+        n.to_string().parse().ok()
+    }
+    fn from_u128(n: u128) -> Option<BigNumber<T>> {
+        Some(BigNumber::from(n))
+    }
+    fn from_f64(n: f64) -> Option<BigNumber<T>> {
+        Some(BigNumber::from(n))
+    }
+}
+// Integers go through their decimal digits, so they are rounded once, to the mantissa
+macro_rules! impl_from_primitive {
+    ($($primitive:ty),*) => {
+        $(
+            impl<T: Mantissa> From<$primitive> for BigNumber<T> {
+                fn from(n: $primitive) -> BigNumber<T> {
+                    n.to_string().parse().unwrap()
+                }
+            }
+        )*
+    };
+}
+impl_from_primitive!(i64, u64, u128);
+impl<T: Mantissa> From<f64> for BigNumber<T> {
+    fn from(n: f64) -> BigNumber<T> {
+        match n.is_finite() {
+            true => n.to_string().parse().unwrap(),
+            false => BigNumber::from_parts(from_f64(n), 0),
+        }
+    }
+}
+// Fails where the f64 would not hold the value: past f64::MAX or below the smallest subnormal
+impl<T: Mantissa> TryFrom<BigNumber<T>> for f64 {
+    type Error = BigNumberError;
+    fn try_from(number: BigNumber<T>) -> Result<f64, BigNumberError> {
+        let value = number.to_f64().unwrap_or(f64::NAN);
+        if (value.is_infinite() && number.is_finite()) {
+            return Err(BigNumberError::new(
+                ErrorCause::ExponentOverflow,
+                &number.to_string(),
+                None,
+            ));
+        }
+        if (value == 0.0 && !number.is_zero()) {
+            return Err(BigNumberError::new(
+                ErrorCause::ExponentUnderflow,
+                &number.to_string(),
+                None,
+            ));
+        }
+        Ok(value)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    // Generic code that only knows num-traits
+    fn mean<N: Num + FromPrimitive + Clone>(values: &[N]) -> N {
+        let sum = values
+            .iter()
+            .cloned()
+            .fold(N::zero(), |sum, value| sum + value);
+        sum / N::from_usize(values.len()).unwrap()
+    }
+    #[test]
+    fn works_in_generic_code() {
+        let masses: Vec<BigNumber> = ["1.989x10^30", "5.972x10^24", "6.39x10^23"]
+            .iter()
+            .map(|written| written.parse().unwrap())
+            .collect();
+        let expected = masses
+            .iter()
+            .map(|mass| mass.to_f64().unwrap())
+            .sum::<f64>()
+            / 3.0;
+        let relative = (mean(&masses).to_f64().unwrap() - expected).abs() / expected;
+        assert!(relative < 1e-6);
+        assert_eq!(mean(&[2.0, 4.0]), 3.0);
+        assert_eq!(
+            BigNumber::<f32>::from_str_radix("-ff.8", 16).unwrap(),
+            BigNumber::new_d(-255.5)
+        );
+        assert_eq!(
+            BigNumber::<f32>::from_str_radix("1.5QD", 10)
+                .unwrap()
+                .exponent,
+            15
+        );
+        let error = BigNumber::<f32>::from_str_radix("10z1", 2).unwrap_err();
+        assert_eq!(
+            (error.cause, error.offset),
+            (ErrorCause::BadPrefix, Some(2))
+        );
+    }
+    #[test]
+    fn signs_and_bounds() {
+        let negative = BigNumber::<f32>::new_d(-2.5);
+        assert_eq!(Signed::abs(&negative), BigNumber::new_d(2.5));
+        assert_eq!(negative.signum(), BigNumber::new_d(-1.0));
+        assert!(negative.is_negative() && !Signed::is_positive(&negative));
+        assert!(!Signed::is_positive(&BigNumber::<f32>::zero()));
+        let signed_zero = BigNumber {
+            base: 0.0_f32,
+            exponent: 0,
+            is_positive: false,
+        };
+        let signed_nan = BigNumber {
+            base: f32::NAN,
+            exponent: 0,
+            is_positive: false,
+        };
+        assert!(!signed_zero.is_negative() && !signed_nan.is_negative());
+        assert_eq!(negative.abs_sub(&BigNumber::new_d(1.0)), BigNumber::zero());
+        let max = BigNumber::<f32>::max_value();
+        assert!(max.is_finite() && max < BigNumber::infinity());
+        assert!(max > "9.9x10^2147483647".parse().unwrap());
+        assert_eq!(BigNumber::<f32>::min_value(), -max);
+    }
+    #[test]
+    fn converts_to_and_from_primitives() {
+        let exact = -123_456_789_012_345_i64;
+        assert_eq!(BigNumber::<f64>::from(exact).to_i64(), Some(exact));
+        assert_eq!(BigNumber::<f64>::from(i64::MIN).to_i64(), None);
+        assert_eq!(
+            BigNumber::<f64>::from(123456789_u64).to_u64(),
+            Some(123456789)
+        );
+        assert_eq!(BigNumber::<f32>::from(u128::MAX).exponent, 38);
+        assert_eq!(BigNumber::<f32>::from(-7.9_f64).to_i64(), Some(-7));
+        assert_eq!(BigNumber::<f32>::from(-7.9_f64).to_u64(), None);
+        assert_eq!(BigNumber::<f32>::from(1e300_f64).exponent, 300);
+        assert_eq!(BigNumber::<f32>::new_d(0.1).to_f64(), Some(0.1));
+        assert_eq!("1x10^40".parse::<BigNumber>().unwrap().to_i128(), None);
+        assert_eq!(f64::try_from(BigNumber::<f32>::new_d(0.1)).unwrap(), 0.1);
+        let too_big = f64::try_from("1x10^400".parse::<BigNumber>().unwrap()).unwrap_err();
+        assert_eq!(too_big.cause, ErrorCause::ExponentOverflow);
+        let too_small = f64::try_from("1x10^-400".parse::<BigNumber>().unwrap()).unwrap_err();
+        assert_eq!(too_small.cause, ErrorCause::ExponentUnderflow);
+        assert!(f64::try_from(BigNumber::<f32>::nan()).unwrap().is_nan());
+    }
+}
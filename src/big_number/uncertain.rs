@@ -0,0 +1,339 @@
+use super::format_policy::{display_policy, FormatPolicy};
+use super::{
+    engineering_exponent, from_f64, is_haven_exponent, write_mantissa, BigNumber, BigNumberError,
+    Mantissa, Notation,
+};
+use num::traits::ToPrimitive;
+use std::{fmt, ops, str::FromStr};
+// A measured value and its standard deviation. Errors are taken as independent and propagated
+// to first order, so sigma stays meaningful while it is small next to the value; an interval
+// [lo, hi] is carried as its midpoint and half-width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Uncertain<T: Mantissa = f32> {
+    pub value: BigNumber<T>,
+    // Never negative
+    pub sigma: BigNumber<T>,
+}
+impl<T: Mantissa> Uncertain<T> {
+    pub fn new(value: BigNumber<T>, sigma: BigNumber<T>) -> Uncertain<T> {
+        Uncertain {
+            value,
+            sigma: sigma.abs(),
+        }
+    }
+    pub fn exact(value: BigNumber<T>) -> Uncertain<T> {
+        Uncertain::new(value, BigNumber::new_d(T::zero()))
+    }
+    pub fn from_bounds(lower: BigNumber<T>, upper: BigNumber<T>) -> Uncertain<T> {
+        Uncertain::new(
+            (lower + upper) / from_f64::<T>(2.0),
+            (upper - lower) / from_f64::<T>(2.0),
+        )
+    }
+    // value ± k sigma, e.g. k = 2 for about 95% confidence
+    pub fn bounds(&self, k: T) -> (BigNumber<T>, BigNumber<T>) {
+        (self.value - self.sigma * k, self.value + self.sigma * k)
+    }
+    // sigma / |value|
+    pub fn relative(&self) -> BigNumber<T> {
+        self.sigma / self.value.abs()
+    }
+    // A zero value raised to a negative power is infinite, and so is its sigma, or NaN when the
+    // zero is exact
+    pub fn powi(&self, n: i32) -> Uncertain<T> {
+        // Exactly 1, where the derivative would be 0 * 0^-1 for a zero value
+        if (n == 0) {
+            return Uncertain::exact(BigNumber::new_d(T::one()));
+        }
+        let derivative = self.value.powi(n - 1) * from_f64::<T>(n as f64);
+        Uncertain::new(self.value.powi(n), derivative * self.sigma)
+    }
+    pub fn sqrt(&self) -> Uncertain<T> {
+        let root = self.value.sqrt();
+        // The slope is infinite at zero, so any uncertainty there is too
+        if (self.value.is_zero()) {
+            return match self.sigma.is_zero() {
+                true => Uncertain::exact(root),
+                false => Uncertain::new(root, BigNumber::infinity()),
+            };
+        }
+        Uncertain::new(root, self.sigma / (root * from_f64::<T>(2.0)))
+    }
+    pub fn display_with<'a>(&'a self, policy: &'a FormatPolicy) -> FormattedUncertain<'a, T> {
+        FormattedUncertain {
+            number: self,
+            policy,
+        }
+    }
+}
+// `number / 10^scale` written with `decimals` digits after the point, rounded to a multiple
+// of 10^`place` when that is above the point. None when the exponents are too far apart for
+// that: the scaled exponent leaves the i32 range or sigma's digits fall past what an f64 holds
+fn write_scaled<T: Mantissa>(number: BigNumber<T>, scale: i32, place: i32) -> Option<String> {
+    let mut scaled = number;
+    if (!scaled.is_zero()) {
+        scaled.decrease_power(scale).ok()?;
+    }
+    let mut value = scaled.to_f64()?;
+    let decimals = scale
+        .checked_sub(place)
+        .filter(|&decimals| decimals <= f64::MAX_10_EXP)?
+        .max(0) as usize;
+    if (place > scale) {
+        let step = 10.0_f64.powi(place - scale);
+        value = (value / step).round() * step;
+    }
+    Some(format!("{:.*}", decimals, value))
+}
+// Writes "1.52 ± 0.03", or "(1.496 ± 0.020)x10^11" when the value needs a power of ten: sigma
+// keeps one significant digit (two when it starts with 1) and the value is rounded to match
+pub struct FormattedUncertain<'a, T: Mantissa> {
+    number: &'a Uncertain<T>,
+    policy: &'a FormatPolicy,
+}
+impl<T: Mantissa> fmt::Display for FormattedUncertain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Uncertain { value, sigma } = *self.number;
+        if (sigma.is_zero()) {
+            return write!(f, "{}", self.policy.format(&value));
+        }
+        if (!value.is_finite() || !sigma.is_finite()) {
+            return write!(
+                f,
+                "{} ± {}",
+                self.policy.format(&value),
+                self.policy.format(&sigma)
+            );
+        }
+        let exponent = match value.is_zero() {
+            true => sigma.exponent,
+            false => value.exponent.max(sigma.exponent),
+        };
+        let notation = self.policy.notation_for(exponent);
+        let scale = match notation {
+            Notation::Scientific => exponent,
+            Notation::Haven if !is_haven_exponent(exponent) => exponent,
+            _ => engineering_exponent(exponent),
+        };
+        let sigma_digits = match (sigma.base < from_f64::<T>(2.0)) {
+            true => 2,
+            false => 1,
+        };
+        let place = sigma.exponent - (sigma_digits - 1);
+        let written = match (
+            write_scaled(value, scale, place),
+            write_scaled(sigma, scale, place),
+        ) {
+            (Some(value), Some(sigma)) => format!("{} ± {}", value, sigma),
+            // Too far apart to share a power of ten, so each gets its own
+            _ => {
+                let scientific = FormatPolicy::new(Notation::Scientific);
+                return write!(
+                    f,
+                    "{} ± {}",
+                    scientific.format(&value),
+                    scientific.format(&sigma)
+                );
+            }
+        };
+        let suffix = match (scale == 0 && !matches!(notation, Notation::SI(_))) {
+            true => String::new(),
+            false => write_mantissa("1", scale, notation).to_string()[1..].to_string(),
+        };
+        match suffix.is_empty() {
+            true => write!(f, "{}", written),
+            false => write!(f, "({}){}", written, suffix),
+        }
+    }
+}
+impl<T: Mantissa> fmt::Display for Uncertain<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.display_with(&display_policy()))
+    }
+}
+// Reads "1.52 ± 0.03", "1.52 +/- 0.03", "(1.496 ± 0.020)x10^11" and plain numbers
+impl<T: Mantissa> FromStr for Uncertain<T> {
+    type Err = BigNumberError;
+    fn from_str(s: &str) -> Result<Uncertain<T>, BigNumberError> {
+        let trimmed = s.trim();
+        let (inner, suffix) = match trimmed.strip_prefix('(').and_then(|x| x.split_once(')')) {
+            Some((inner, suffix)) => (inner, suffix),
+            None => (trimmed, ""),
+        };
+        let parts = inner.split_once('±').or_else(|| inner.split_once("+/-"));
+        let parse = |part: &str| format!("{}{}", part.trim(), suffix).parse::<BigNumber<T>>();
+        match parts {
+            Some((value, sigma)) => Ok(Uncertain::new(parse(value)?, parse(sigma)?)),
+            None => Ok(Uncertain::exact(parse(inner)?)),
+        }
+    }
+}
+impl<T: Mantissa> From<BigNumber<T>> for Uncertain<T> {
+    fn from(value: BigNumber<T>) -> Uncertain<T> {
+        Uncertain::exact(value)
+    }
+}
+impl<T: Mantissa> ops::Neg for Uncertain<T> {
+    type Output = Uncertain<T>;
+    fn neg(self) -> Uncertain<T> {
+        Uncertain::new(-self.value, self.sigma)
+    }
+}
+impl<T: Mantissa> ops::Add for Uncertain<T> {
+    type Output = Uncertain<T>;
+    fn add(self, other: Uncertain<T>) -> Uncertain<T> {
+        Uncertain::new(self.value + other.value, self.sigma.hypot(&other.sigma))
+    }
+}
+impl<T: Mantissa> ops::Sub for Uncertain<T> {
+    type Output = Uncertain<T>;
+    fn sub(self, other: Uncertain<T>) -> Uncertain<T> {
+        self + (-other)
+    }
+}
+impl<T: Mantissa> ops::Mul for Uncertain<T> {
+    type Output = Uncertain<T>;
+    fn mul(self, other: Uncertain<T>) -> Uncertain<T> {
+        let sigma = (self.sigma * other.value).hypot(&(other.sigma * self.value));
+        Uncertain::new(self.value * other.value, sigma)
+    }
+}
+impl<T: Mantissa> ops::Div for Uncertain<T> {
+    type Output = Uncertain<T>;
+    fn div(self, other: Uncertain<T>) -> Uncertain<T> {
+        let quotient = self.value / other.value;
+        let sigma = (self.sigma / other.value).hypot(&(quotient * other.sigma / other.value));
+        Uncertain::new(quotient, sigma)
+    }
+}
+// Exact operands: BigNumbers and plain floats scale the value and sigma alike
+macro_rules! impl_uncertain_operations {
+    ($($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {
+        $(
+            impl<T: Mantissa> ops::$trait<BigNumber<T>> for Uncertain<T> {
+                type Output = Uncertain<T>;
+                fn $method(self, rhs: BigNumber<T>) -> Uncertain<T> {
+                    ops::$trait::$method(self, Uncertain::exact(rhs))
+                }
+            }
+            impl<T: Mantissa> ops::$trait<T> for Uncertain<T> {
+                type Output = Uncertain<T>;
+                fn $method(self, rhs: T) -> Uncertain<T> {
+                    ops::$trait::$method(self, Uncertain::exact(BigNumber::new_d(rhs)))
+                }
+            }
+            impl<T: Mantissa> ops::$assign_trait for Uncertain<T> {
+                fn $assign_method(&mut self, rhs: Uncertain<T>) {
+                    *self = ops::$trait::$method(*self, rhs);
+                }
+            }
+            impl<T: Mantissa> ops::$assign_trait<T> for Uncertain<T> {
+                fn $assign_method(&mut self, rhs: T) {
+                    *self = ops::$trait::$method(*self, rhs);
+                }
+            }
+        )*
+    };
+}
+impl_uncertain_operations!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign
+);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn uncertain(written: &str) -> Uncertain<f64> {
+        written.parse().unwrap()
+    }
+    fn assert_close(actual: BigNumber<f64>, expected: f64) {
+        let relative = (actual.to_float() - expected).abs() / expected.abs();
+        assert!(relative < 1e-12, "{} is not {}", actual, expected);
+    }
+    #[test]
+    fn propagates_independent_errors() {
+        let a = uncertain("10 ± 3");
+        let b = uncertain("20 +/- 4");
+        assert_close((a + b).sigma, 5.0);
+        assert_close((a - b).sigma, 5.0);
+        assert_close((a - b).value, -10.0);
+        let product = a * b;
+        assert_close(product.value, 200.0);
+        assert_close(product.sigma, 200.0 * (0.09_f64 + 0.04).sqrt());
+        let quotient = a / b;
+        assert_close(quotient.sigma, 0.5 * (0.09_f64 + 0.04).sqrt());
+        assert_close(a.powi(3).sigma, 3.0 * 100.0 * 3.0);
+        assert_close(b.sqrt().sigma, 4.0 / (2.0 * 20.0_f64.sqrt()));
+        assert_close((a * 2.0).sigma, 6.0);
+        assert_close((-a).sigma, 3.0);
+        let (lower, upper) = a.bounds(2.0);
+        assert_close(lower, 4.0);
+        assert_close(upper, 16.0);
+        let interval = Uncertain::from_bounds(BigNumber::new_d(1.0), BigNumber::new_d(2.0));
+        assert_close(interval.value, 1.5);
+        assert_close(interval.sigma, 0.5);
+    }
+    #[test]
+    fn handles_zero_values() {
+        let zero = uncertain("0 ± 0.5");
+        assert_eq!(zero.sqrt().value, BigNumber::new_d(0.0));
+        assert!(zero.sqrt().sigma.is_infinite());
+        assert_eq!(
+            Uncertain::exact(BigNumber::new_d(0.0)).sqrt().sigma,
+            BigNumber::new_d(0.0)
+        );
+        assert_eq!(zero.powi(0), Uncertain::exact(BigNumber::new_d(1.0)));
+        assert_eq!(zero.powi(2).sigma, BigNumber::new_d(0.0));
+        assert!(zero.powi(-1).value.is_infinite());
+        assert!(zero.powi(-1).sigma.is_infinite());
+    }
+    #[test]
+    fn writes_distant_exponents_separately() {
+        let far = uncertain("1x10^2000000000 ± 3x10^-2000000000");
+        assert_eq!(
+            far.display_with(&FormatPolicy::default()).to_string(),
+            "1x10^2000000000 ± 3x10^-2000000000"
+        );
+        let tiny_sigma = uncertain("1x10^1000000 ± 2");
+        assert_eq!(
+            tiny_sigma
+                .display_with(&FormatPolicy::default())
+                .to_string(),
+            "1x10^1000000 ± 2x10^0"
+        );
+    }
+    #[test]
+    fn writes_value_and_sigma_together() {
+        let policy = FormatPolicy::default();
+        let written = |x: Uncertain<f64>| x.display_with(&policy).to_string();
+        assert_eq!(written(uncertain("1.52 ± 0.03")), "1.52 ± 0.03");
+        assert_eq!(written(uncertain("1.5234 ± 0.0149")), "1.523 ± 0.015");
+        assert_eq!(written(uncertain("-123456 ± 2000")), "(-123 ± 2)K");
+        assert_eq!(written(uncertain("1.496x10^11 ± 2x10^9")), "(150 ± 2)B");
+        assert_eq!(written(uncertain("7 ± 0")), "7");
+        let scientific = FormatPolicy::new(Notation::Scientific);
+        assert_eq!(
+            uncertain("1.496x10^11 ± 2x10^9")
+                .display_with(&scientific)
+                .to_string(),
+            "(1.50 ± 0.02)x10^11"
+        );
+        let si = FormatPolicy::new(Notation::SI("m".to_string()));
+        assert_eq!(
+            uncertain("2.27x10^11 ± 4.6x10^9")
+                .display_with(&si)
+                .to_string(),
+            "(227 ± 5) Gm"
+        );
+        for written in ["(1.50 ± 0.02)x10^11", "(227 ± 5) Gm", "(-123 ± 2)K"] {
+            let read = uncertain(written);
+            let policy = match written.contains('G') {
+                true => si.clone(),
+                false if written.contains('x') => scientific.clone(),
+                false => policy.clone(),
+            };
+            assert_eq!(read.display_with(&policy).to_string(), written);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+#![allow(unused_parens)]
+// Evaluates BigNumber expressions, see `orbital_system::calculator` for the syntax.
+//     bigcalc [--format haven|scientific|engineering|si[:unit]] [--digits N] [EXPRESSION...]
+// With an expression it prints the result and exits, otherwise it reads one expression per
+// line. ":format <name>" and ":digits <n|off>" change how later results are written.
+use orbital_system::big_number::format_policy::FormatPolicy;
+use orbital_system::big_number::Notation;
+use orbital_system::calculator::{parse_notation, Calculator};
+use std::env;
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+struct Settings {
+    notation: Notation,
+    digits: Option<u32>,
+}
+impl Settings {
+    fn policy(&self) -> FormatPolicy {
+        let policy = FormatPolicy::new(self.notation.clone());
+        match self.digits {
+            Some(digits) => policy.with_significant_digits(digits),
+            None => policy,
+        }
+    }
+    // Applies one "--format"/":format" style option
+    fn set(&mut self, option: &str, value: &str) -> Result<(), String> {
+        match option {
+            "format" => {
+                self.notation =
+                    parse_notation(value).ok_or_else(|| format!("unknown format \"{}\"", value))?
+            }
+            "digits" => {
+                self.digits = match value {
+                    "off" => None,
+                    _ => Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("expected a digit count, found \"{}\"", value))?,
+                    ),
+                }
+            }
+            _ => return Err(format!("unknown option \"{}\"", option)),
+        }
+        Ok(())
+    }
+}
+fn main() -> ExitCode {
+    let mut settings = Settings {
+        notation: Notation::Haven,
+        digits: None,
+    };
+    let mut arguments = env::args().skip(1);
+    let mut expression = Vec::new();
+    while let Some(argument) = arguments.next() {
+        match argument.strip_prefix("--") {
+            Some(option) => {
+                let value = arguments.next().unwrap_or_default();
+                if let Err(message) = settings.set(option, &value) {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            }
+            None => expression.push(argument),
+        }
+    }
+    let mut calculator = Calculator::new();
+    if (!expression.is_empty()) {
+        return match calculator.evaluate(&expression.join(" ")) {
+            Ok(answer) => {
+                println!("{}", answer.format(&settings.policy()));
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return ExitCode::SUCCESS,
+        };
+        let line = line.trim();
+        if (line.is_empty()) {
+            continue;
+        }
+        if (line == "quit" || line == "exit") {
+            return ExitCode::SUCCESS;
+        }
+        if let Some(command) = line.strip_prefix(':') {
+            let (option, value) = command.split_once(' ').unwrap_or((command, ""));
+            if let Err(message) = settings.set(option, value.trim()) {
+                eprintln!("error: {}", message);
+            }
+            continue;
+        }
+        match calculator.evaluate(line) {
+            Ok(answer) => println!("{}", answer.format(&settings.policy())),
+            Err(error) => eprintln!("error: {}", error),
+        }
+    }
+}
@@ -0,0 +1,561 @@
+use crate::big_number::format_policy::FormatPolicy;
+use crate::big_number::{BigNumber, BigNumberError, Notation};
+use crate::quantity::{LengthUnit, MassUnit, TimeUnit, Unit};
+use std::{error::Error, f64::consts, fmt};
+// Expressions over BigNumber<f64> for checking results by hand, used by the bigcalc binary:
+//     1.5QD * 3.2x10^18 / (2 + 3)^2
+//     sqrt(2 AU * 3 km) in AU
+// Literals are anything BigNumber reads without spaces (Haven suffixes, "x10^"), a unit written
+// after a value multiplies it by the unit in SI base units, and a trailing "in <unit>" divides
+// the result back out. `ans` is the previous result.
+const CONSTANTS: [(&str, f64); 4] = [
+    ("pi", consts::PI),
+    ("e", consts::E),
+    // m^3 kg^-1 s^-2
+    ("G", 6.674_30e-11),
+    // m/s
+    ("c", 299_792_458.0),
+];
+const FUNCTIONS: [&str; 10] = [
+    "sqrt", "cbrt", "abs", "ln", "log10", "exp", "hypot", "min", "max", "round",
+];
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CalculatorError {
+    Number(BigNumberError),
+    // Character offset and what was found there
+    UnexpectedCharacter(usize, char),
+    UnexpectedToken(usize, String),
+    UnexpectedEnd,
+    UnknownName(usize, String),
+    UnknownUnit(usize, String),
+    ArgumentCount {
+        function: String,
+        expected: usize,
+        found: usize,
+    },
+    // The operation the value is outside the domain of, e.g. "sqrt of a negative number"
+    Domain(&'static str),
+    // `ans` before anything was evaluated
+    NoPreviousResult,
+}
+impl fmt::Display for CalculatorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CalculatorError::Number(error) => write!(f, "{}", error),
+            CalculatorError::UnexpectedCharacter(offset, found) => {
+                write!(f, "unexpected {:?} at character {}", found, offset)
+            }
+            CalculatorError::UnexpectedToken(offset, found) => {
+                write!(f, "unexpected \"{}\" at character {}", found, offset)
+            }
+            CalculatorError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            CalculatorError::UnknownName(offset, name) => {
+                write!(f, "unknown name \"{}\" at character {}", name, offset)
+            }
+            CalculatorError::UnknownUnit(offset, name) => {
+                write!(f, "unknown unit \"{}\" at character {}", name, offset)
+            }
+            CalculatorError::ArgumentCount {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{} takes {} argument(s), found {}",
+                function, expected, found
+            ),
+            CalculatorError::Domain(operation) => write!(f, "{}", operation),
+            CalculatorError::NoPreviousResult => write!(f, "no previous result for ans"),
+        }
+    }
+}
+impl Error for CalculatorError {}
+impl From<BigNumberError> for CalculatorError {
+    fn from(error: BigNumberError) -> CalculatorError {
+        CalculatorError::Number(error)
+    }
+}
+// Symbol and size in SI base units of every unit a value can be written in
+fn units() -> Vec<(&'static str, f64)> {
+    let lengths = [
+        LengthUnit::Meter,
+        LengthUnit::Kilometer,
+        LengthUnit::AstronomicalUnit,
+        LengthUnit::LightYear,
+        LengthUnit::Parsec,
+    ]
+    .map(|unit| (unit.symbol(), unit.factor()));
+    let times = [TimeUnit::Second, TimeUnit::Day, TimeUnit::Year]
+        .map(|unit| (unit.symbol(), unit.factor()));
+    // M☉ and M⊕ cannot be typed easily, so they also go by Msun and Mearth
+    let masses = [
+        ("kg", MassUnit::Kilogram.factor()),
+        ("Msun", MassUnit::SolarMass.factor()),
+        ("Mearth", MassUnit::EarthMass.factor()),
+    ];
+    lengths
+        .into_iter()
+        .chain(times)
+        .chain(masses)
+        .collect::<Vec<(&str, f64)>>()
+}
+fn unit(name: &str) -> Option<(&'static str, f64)> {
+    units().into_iter().find(|(symbol, _)| *symbol == name)
+}
+// "haven", "scientific"/"sci", "engineering"/"eng", "si" or "si:<unit>"
+pub fn parse_notation(name: &str) -> Option<Notation> {
+    match name.split_once(':') {
+        Some(("si", unit)) => Some(Notation::SI(unit.to_string())),
+        Some(_) => None,
+        None => match name {
+            "haven" => Some(Notation::Haven),
+            "scientific" | "sci" => Some(Notation::Scientific),
+            "engineering" | "eng" => Some(Notation::Engineering),
+            "si" => Some(Notation::SI(String::new())),
+            _ => None,
+        },
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(BigNumber<f64>),
+    Name(String),
+    Operator(char),
+}
+// Tokens with the character offset they start at
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, CalculatorError> {
+    let chars = input.chars().collect::<Vec<char>>();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    let take_while = |start: usize, f: &dyn Fn(char) -> bool| {
+        let mut end = start;
+        while (end < chars.len() && f(chars[end])) {
+            end += 1;
+        }
+        end
+    };
+    while (index < chars.len()) {
+        let char = chars[index];
+        if (char.is_whitespace()) {
+            index += 1;
+        } else if (char.is_ascii_digit() || char == '.') {
+            let mut end = take_while(index, &|x| x.is_ascii_digit() || x == '.');
+            let exponent: String = chars[end..].iter().take(4).collect();
+            if (exponent == "x10^") {
+                let sign = match chars.get(end + 4) {
+                    Some('-') | Some('+') => 1,
+                    _ => 0,
+                };
+                end = take_while(end + 4 + sign, &|x| x.is_ascii_digit());
+            }
+            // A Haven suffix belongs to the number, anything else is a unit after it
+            let suffix_end = take_while(end, &|x| x.is_alphabetic());
+            let with_suffix: String = chars[index..suffix_end].iter().collect();
+            let (number, end) = match with_suffix.parse::<BigNumber<f64>>() {
+                Ok(number) if suffix_end > end => (number, suffix_end),
+                _ => {
+                    let written: String = chars[index..end].iter().collect();
+                    // Number errors point into the literal, move them to where it starts
+                    let number = written.parse::<BigNumber<f64>>().map_err(|mut error| {
+                        error.offset = error.offset.map(|x| x + index);
+                        CalculatorError::Number(error)
+                    })?;
+                    (number, end)
+                }
+            };
+            tokens.push((index, Token::Number(number)));
+            index = end;
+        } else if (char.is_alphabetic() || char == '_') {
+            let end = take_while(index, &|x| x.is_alphanumeric() || x == '_');
+            tokens.push((index, Token::Name(chars[index..end].iter().collect())));
+            index = end;
+        } else if ("+-*/%^(),".contains(char)) {
+            tokens.push((index, Token::Operator(char)));
+            index += 1;
+        } else {
+            return Err(CalculatorError::UnexpectedCharacter(index, char));
+        }
+    }
+    Ok(tokens)
+}
+// Recursive descent, loosest binding first:
+//     statement := expression ("in" unit)?
+//     expression := term (("+" | "-") term)*
+//     term := unary (("*" | "/" | "%") unary)*
+//     unary := ("-" | "+") unary | power
+//     power := postfix ("^" unary)?
+//     postfix := primary unit*
+//     primary := number | name | name "(" arguments ")" | "(" expression ")"
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    position: usize,
+    previous: Option<&'a BigNumber<f64>>,
+}
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(_, token)| token)
+    }
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.position)
+            .map_or(0, |(offset, _)| *offset)
+    }
+    fn next(&mut self) -> Result<(usize, Token), CalculatorError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or(CalculatorError::UnexpectedEnd)?;
+        self.position += 1;
+        Ok(token)
+    }
+    fn eat(&mut self, operator: char) -> bool {
+        if (self.peek() == Some(&Token::Operator(operator))) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+    fn expect(&mut self, operator: char) -> Result<(), CalculatorError> {
+        match self.next()? {
+            (_, Token::Operator(x)) if (x == operator) => Ok(()),
+            (offset, token) => Err(unexpected(offset, &token)),
+        }
+    }
+    fn statement(&mut self) -> Result<Answer, CalculatorError> {
+        let value = self.expression()?;
+        let answer = match self.peek() {
+            Some(Token::Name(name)) if (name == "in") => {
+                self.position += 1;
+                let (offset, token) = self.next()?;
+                let (symbol, factor) = match &token {
+                    Token::Name(name) => {
+                        unit(name).ok_or(CalculatorError::UnknownUnit(offset, name.clone()))?
+                    }
+                    _ => return Err(unexpected(offset, &token)),
+                };
+                Answer {
+                    value: value / factor,
+                    unit: Some(symbol),
+                }
+            }
+            _ => Answer { value, unit: None },
+        };
+        match self.peek() {
+            None => Ok(answer),
+            Some(token) => Err(unexpected(self.offset(), token)),
+        }
+    }
+    fn expression(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        let mut value = self.term()?;
+        loop {
+            if (self.eat('+')) {
+                value += self.term()?;
+            } else if (self.eat('-')) {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+    fn term(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        let mut value = self.unary()?;
+        loop {
+            if (self.eat('*')) {
+                value *= self.unary()?;
+            } else if (self.eat('/')) {
+                value = divide(value, self.unary()?)?;
+            } else if (self.eat('%')) {
+                let divisor = self.unary()?;
+                if (divisor.is_zero()) {
+                    return Err(CalculatorError::Domain("remainder of a division by zero"));
+                }
+                value %= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+    fn unary(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        if (self.eat('-')) {
+            return Ok(-self.unary()?);
+        }
+        if (self.eat('+')) {
+            return self.unary();
+        }
+        self.power()
+    }
+    fn power(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        let base = self.postfix()?;
+        match self.eat('^') {
+            true => power(base, self.unary()?),
+            false => Ok(base),
+        }
+    }
+    fn postfix(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        let mut value = self.primary()?;
+        while let Some(Token::Name(name)) = self.peek() {
+            match unit(name) {
+                Some((_, factor)) => {
+                    value *= factor;
+                    self.position += 1;
+                }
+                None => break,
+            }
+        }
+        Ok(value)
+    }
+    fn primary(&mut self) -> Result<BigNumber<f64>, CalculatorError> {
+        match self.next()? {
+            (_, Token::Number(number)) => Ok(number),
+            (_, Token::Operator('(')) => {
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            (_, Token::Name(name)) if (FUNCTIONS.contains(&name.as_str())) => {
+                self.expect('(')?;
+                let mut arguments = vec![self.expression()?];
+                while (self.eat(',')) {
+                    arguments.push(self.expression()?);
+                }
+                self.expect(')')?;
+                call(&name, &arguments)
+            }
+            (offset, Token::Name(name)) => {
+                if (name == "ans") {
+                    return self
+                        .previous
+                        .copied()
+                        .ok_or(CalculatorError::NoPreviousResult);
+                }
+                if let Some((_, value)) = CONSTANTS.iter().find(|(x, _)| *x == name) {
+                    return Ok(BigNumber::new_d(*value));
+                }
+                match unit(&name) {
+                    Some((_, factor)) => Ok(BigNumber::new_d(factor)),
+                    None => Err(CalculatorError::UnknownName(offset, name)),
+                }
+            }
+            (offset, token) => Err(unexpected(offset, &token)),
+        }
+    }
+}
+fn unexpected(offset: usize, token: &Token) -> CalculatorError {
+    CalculatorError::UnexpectedToken(
+        offset,
+        match token {
+            Token::Number(number) => number.to_string(),
+            Token::Name(name) => name.clone(),
+            Token::Operator(operator) => operator.to_string(),
+        },
+    )
+}
+fn divide(a: BigNumber<f64>, b: BigNumber<f64>) -> Result<BigNumber<f64>, CalculatorError> {
+    match b.is_zero() {
+        true => Err(CalculatorError::Domain("division by zero")),
+        false => Ok(a / b),
+    }
+}
+fn check_exponent(log10: f64) -> Result<(), CalculatorError> {
+    match (log10.abs() < i32::MAX as f64) {
+        true => Ok(()),
+        false => Err(CalculatorError::Domain("result exponent out of range")),
+    }
+}
+fn power(
+    base: BigNumber<f64>,
+    exponent: BigNumber<f64>,
+) -> Result<BigNumber<f64>, CalculatorError> {
+    let n = exponent.to_float();
+    if (base.is_zero()) {
+        return match (n > 0.0) {
+            true => Ok(base),
+            false if (n == 0.0) => Ok(BigNumber::new_d(1.0)),
+            false => Err(CalculatorError::Domain("division by zero")),
+        };
+    }
+    let is_integer = n.fract() == 0.0;
+    if (!base.is_positive && !is_integer) {
+        return Err(CalculatorError::Domain(
+            "fractional power of a negative number",
+        ));
+    }
+    check_exponent(n * (base.exponent as f64 + base.base.abs().log10()))?;
+    Ok(base.powf(n))
+}
+fn call(function: &str, arguments: &[BigNumber<f64>]) -> Result<BigNumber<f64>, CalculatorError> {
+    let expected = match function {
+        "hypot" | "min" | "max" => 2,
+        _ => 1,
+    };
+    if (arguments.len() != expected) {
+        return Err(CalculatorError::ArgumentCount {
+            function: function.to_string(),
+            expected,
+            found: arguments.len(),
+        });
+    }
+    let x = arguments[0];
+    let is_non_positive = !x.is_positive || x.is_zero();
+    Ok(match function {
+        "sqrt" if (!x.is_positive && !x.is_zero()) => {
+            return Err(CalculatorError::Domain("square root of a negative number"))
+        }
+        "ln" | "log10" if (is_non_positive) => {
+            return Err(CalculatorError::Domain(
+                "logarithm of a non-positive number",
+            ))
+        }
+        "exp" => {
+            check_exponent(x.to_float() * consts::LOG10_E)?;
+            x.exp()
+        }
+        "sqrt" => x.sqrt(),
+        "cbrt" => x.cbrt(),
+        "abs" => x.abs(),
+        "ln" => x.ln(),
+        "log10" => x.log10(),
+        "hypot" => x.hypot(&arguments[1]),
+        "min" => x.min(arguments[1]),
+        "max" => x.max(arguments[1]),
+        "round" => x.round(),
+        _ => unreachable!(),
+    })
+}
+// A result and the unit it was converted to with "in", if any
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Answer {
+    pub value: BigNumber<f64>,
+    pub unit: Option<&'static str>,
+}
+impl Answer {
+    pub fn format(&self, policy: &FormatPolicy) -> String {
+        match (self.unit, policy.preferred()) {
+            // SI writes the prefix in front of the unit, "1.496 Gm"
+            (Some(unit), Notation::SI(_)) => {
+                let policy = policy
+                    .clone()
+                    .with_preferred(Notation::SI(unit.to_string()));
+                policy.format(&self.value)
+            }
+            (Some(unit), _) => format!("{} {}", policy.format(&self.value), unit),
+            (None, _) => policy.format(&self.value),
+        }
+    }
+}
+#[derive(Debug, Clone, Default)]
+pub struct Calculator {
+    previous: Option<BigNumber<f64>>,
+}
+impl Calculator {
+    pub fn new() -> Calculator {
+        Calculator::default()
+    }
+    pub fn evaluate(&mut self, input: &str) -> Result<Answer, CalculatorError> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+            previous: self.previous.as_ref(),
+        };
+        let answer = parser.statement()?;
+        self.previous = Some(answer.value);
+        Ok(answer)
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn evaluate(input: &str) -> String {
+        let answer = Calculator::new().evaluate(input).unwrap();
+        answer.format(&FormatPolicy::new(Notation::Scientific).with_significant_digits(6))
+    }
+    fn error(input: &str) -> CalculatorError {
+        Calculator::new().evaluate(input).unwrap_err()
+    }
+    #[test]
+    fn evaluates_with_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), "7x10^0");
+        assert_eq!(evaluate("(1 + 2) * 3"), "9x10^0");
+        assert_eq!(evaluate("2^3^2"), "5.12x10^2");
+        assert_eq!(evaluate("-2^2"), "-4x10^0");
+        assert_eq!(evaluate("2^-1"), "5x10^-1");
+        assert_eq!(evaluate("10 - 4 - 3"), "3x10^0");
+        assert_eq!(evaluate("7 % 4"), "3x10^0");
+        assert_eq!(evaluate("hypot(3, 4) + sqrt(16) - abs(-1)"), "8x10^0");
+        assert_eq!(evaluate("round(2.5) + round(-0.4)"), "3x10^0");
+        assert_eq!(evaluate("round(0.6)"), "1x10^0");
+        assert_eq!(evaluate("round(1234.5678)"), "1.235x10^3");
+        assert_eq!(evaluate("round(3x10^-5)"), "0.0");
+    }
+    #[test]
+    fn reads_haven_and_scientific_literals() {
+        assert_eq!(evaluate("1.5QD"), "1.5x10^15");
+        assert_eq!(evaluate("1.5QD * 3.2x10^18"), "4.8x10^33");
+        assert_eq!(evaluate("3.2x10^-18 / 2"), "1.6x10^-18");
+        assert_eq!(evaluate("1x10^5000 ^ 2"), "1x10^10000");
+        assert_eq!(evaluate("round(1.5x10^400)"), "1.5x10^400");
+        let haven = Calculator::new().evaluate("2K * 3M").unwrap();
+        assert_eq!(haven.format(&FormatPolicy::default()), "6B");
+    }
+    #[test]
+    fn converts_units() {
+        assert_eq!(evaluate("1 AU in km"), "1.49598x10^8 km");
+        assert_eq!(evaluate("1 AU / c in s"), "4.99005x10^2 s");
+        assert_eq!(evaluate("1 ly in AU"), "6.32411x10^4 AU");
+        let si = FormatPolicy::new(Notation::SI(String::new())).with_significant_digits(4);
+        let answer = Calculator::new().evaluate("1AU in m").unwrap();
+        assert_eq!(answer.format(&si), "149.6 Gm");
+    }
+    #[test]
+    fn remembers_the_previous_result() {
+        let mut calculator = Calculator::new();
+        assert_eq!(error("ans"), CalculatorError::NoPreviousResult);
+        calculator.evaluate("6").unwrap();
+        assert_eq!(
+            calculator.evaluate("ans * 7").unwrap().value,
+            BigNumber::new_d(42.0)
+        );
+    }
+    #[test]
+    fn reports_errors() {
+        assert_eq!(error("1 +"), CalculatorError::UnexpectedEnd);
+        assert_eq!(error("1 $ 2"), CalculatorError::UnexpectedCharacter(2, '$'));
+        assert_eq!(
+            error("2 * foo"),
+            CalculatorError::UnknownName(4, "foo".to_string())
+        );
+        assert_eq!(
+            error("1 in foo"),
+            CalculatorError::UnknownUnit(5, "foo".to_string())
+        );
+        assert_eq!(
+            error("(1 2)"),
+            CalculatorError::UnexpectedToken(3, "2".to_string())
+        );
+        assert_eq!(
+            error("1 / (2 - 2)"),
+            CalculatorError::Domain("division by zero")
+        );
+        assert_eq!(
+            error("sqrt(-1)"),
+            CalculatorError::Domain("square root of a negative number")
+        );
+        assert_eq!(
+            error("hypot(1)"),
+            CalculatorError::ArgumentCount {
+                function: "hypot".to_string(),
+                expected: 2,
+                found: 1,
+            }
+        );
+        assert!(matches!(error("10^10^10"), CalculatorError::Domain(_)));
+        assert!(matches!(error("1.2.3"), CalculatorError::Number(_)));
+    }
+    #[test]
+    fn names_notations() {
+        assert_eq!(parse_notation("sci"), Some(Notation::Scientific));
+        assert_eq!(parse_notation("si:m"), Some(Notation::SI("m".to_string())));
+        assert_eq!(parse_notation("roman"), None);
+    }
+}
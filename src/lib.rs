@@ -0,0 +1,5 @@
+#![allow(unused_parens)]
+pub mod big_number;
+pub mod calculator;
+pub mod physics;
+pub mod quantity;
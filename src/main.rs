@@ -3,10 +3,8 @@
 #![allow(unused_variables)]
 extern crate specs;
 use std::collections::HashMap;
-use std::default;
 use std::env;
-use std::f32::consts::PI;
-use std::fmt::format;
+use std::fmt;
 use std::fs::File;
 use std::io;
 use std::io::BufRead;
@@ -14,12 +12,16 @@ use std::io::BufReader;
 use std::ops::Mul;
 use std::path::Path;
 
+use orbital_system::big_number;
+use orbital_system::physics;
+use orbital_system::quantity::{Length, LengthUnit, Mass, MassUnit, Time, TimeUnit, Unit};
+
+use big_number::format_policy::{FormatPolicy, FormatState};
+use big_number::uncertain::Uncertain;
 use big_number::BigNumber;
 use big_number::BigVec2;
+use big_number::Notation;
 
-use ::rand::random;
-use big_number::Format;
-use macroquad::color;
 use macroquad::color::BLACK;
 use macroquad::math::Vec2;
 use macroquad::prelude::*;
@@ -29,6 +31,10 @@ use macroquad::window::next_frame;
 use macroquad::window::request_new_screen_size;
 use macroquad::window::Conf;
 use num::traits::real::Real;
+use physics::adaptive::{DormandPrince, Tolerance};
+use physics::clock::SimClock;
+use physics::gravity::{self, Body, Orbit};
+use physics::integrator::{Integrator, RungeKutta4, SemiImplicitEuler, VelocityVerlet, Yoshida4};
 use physics::update_bodies;
 use physics::AccelerationType;
 use physics::RigidBody;
@@ -36,11 +42,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Deserializer;
 use serde_json::Value;
 use specs::prelude::*;
-mod big_number;
-mod physics;
 
-const SUN_MASS: f32 = 1.0;
-const ASTRONOMICAL_UNIT: f32 = 149597871.0;
 const FONT_SIZE: f32 = 32.0;
 // Hierarchy: Sun => Planet => Moon
 // Planets are unscaled for the sake of visualization purposes
@@ -63,7 +65,7 @@ struct Sun {
 }
 impl SpaceObject for Sun {
     fn get_position(&self) -> BigVec2 {
-        self.position.clone()
+        self.position
     }
     fn get_radius(&self) -> f32 {
         self.radius
@@ -92,7 +94,6 @@ impl Component for Sun {
 #[derive(Clone, Debug)]
 struct Planet {
     position: BigVec2,
-    focus: Vec2,
     radius: f32,
     color: Vec<Color>,
     current_color: Color,
@@ -102,7 +103,7 @@ struct Planet {
 }
 impl SpaceObject for Planet {
     fn get_position(&self) -> BigVec2 {
-        self.position.clone()
+        self.position
     }
     fn get_radius(&self) -> f32 {
         self.radius
@@ -140,7 +141,7 @@ struct IsOrbital {
 }
 impl SpaceObject for Moon {
     fn get_position(&self) -> BigVec2 {
-        self.position.clone()
+        self.position
     }
     fn get_radius(&self) -> f32 {
         self.radius
@@ -217,11 +218,13 @@ mod easing_styles {
     }
 }
 fn map_world_to_screen_space(number: BigNumber) -> f32 {
-    let mut number_clone = number.clone();
-    number_clone.decrease_power(
-        number_clone.exponent
-            - (map_ranges(number_clone.exponent as f32, 7.0, 8.0, 1.0, 2.0) as i32),
-    );
+    let mut number_clone = number;
+    number_clone
+        .decrease_power(
+            number_clone.exponent
+                - (map_ranges(number_clone.exponent as f32, 7.0, 8.0, 1.0, 2.0) as i32),
+        )
+        .unwrap();
     number_clone.base * Real::powf(10.0, number_clone.exponent as f32)
 }
 fn map_screen_to_world_space(number: f32) -> BigNumber {
@@ -269,33 +272,19 @@ fn lerp_color<T: SpaceObject>(object: &mut T) {
         ),
         1.0,
     ));
-    ()
-}
-// Elliptical Functions
-// I reverse-engineered these formulae into code myself
-fn radius_of_ellipse_from_theta(theta: f32, eccentricity: f32, major_axis: f32) -> f32 {
-    let semi_major_axis = major_axis / 2.0;
-    let semi_lactus_rect = semi_major_axis * (1.0 - Real::powi(eccentricity, 2));
-    semi_lactus_rect / (1.0 + (eccentricity * f32::cos(theta)))
-}
-fn get_ellispe_period(major_axis: f32, gravitational_constant: f32) -> f32 {
-    f32::sqrt(
-        (4.0 * Real::powi(PI, 2) * Real::powi(major_axis, 3)) / (gravitational_constant * SUN_MASS),
-    )
 }
-fn get_delta_theta(
-    current_theta: f32,
-    eccentricity: f32,
-    major_axis: f32,
-    gravitational_constant: f32,
-) -> f32 {
-    let semi_major_axis = major_axis / 2.0;
-    let semi_minor_axis = semi_major_axis * f32::sqrt(1.0 - Real::powi(eccentricity, 2));
-    let period = get_ellispe_period(major_axis, gravitational_constant);
-    let mean_motion = (2.0 * PI) / period;
-    let radius = radius_of_ellipse_from_theta(current_theta, eccentricity, major_axis);
-    (semi_minor_axis * semi_major_axis * mean_motion * get_frame_time() * 1000.0)
-        / (Real::powi(radius, 2))
+// Simulated seconds per real second go from 10^0 to 10^`MAX_TIME_WARP`, see `TimeWarp`
+const MAX_TIME_WARP: i32 = 7;
+// Simulated time per tick of the `SimClock`, which is also the fixed integrators' step
+const TICK_DAYS: f64 = 0.05;
+// The simulation works in meters from the center of mass with y up, world coordinates are km
+// with the center of mass in the middle of the screen and y down
+fn world_position(position: BigVec2<f64>) -> BigVec2 {
+    let kilometers = position.cast::<f32>() / 1000.0;
+    BigVec2::new(
+        map_screen_to_world_space(screen_width() / 2.0) + kilometers.x,
+        map_screen_to_world_space(screen_height() / 2.0) - kilometers.y,
+    )
 }
 //
 struct DrawObject;
@@ -303,9 +292,11 @@ struct ColorLerp;
 struct AddBackgroundStars;
 struct UpdateBackgroundStars;
 struct DestroyBackgroundStars;
+struct SimulateGravity;
 struct UpdatePlanetPositions;
 struct DrawTextAbovePlanets;
 struct DisplayPlanetInformation;
+struct DisplaySimulationInformation;
 
 struct Renderable;
 impl Component for Renderable {
@@ -325,7 +316,6 @@ impl<'a> System<'a> for ColorLerp {
                 lerp_color(moon);
             }
         });
-        ()
     }
 }
 impl<'a> System<'a> for DrawObject {
@@ -401,13 +391,23 @@ impl<'a> System<'a> for UpdateBackgroundStars {
         WriteStorage<'a, BackgroundStars>,
         ReadStorage<'a, RigidBody>,
         Read<'a, LazyUpdate>,
+        ReadExpect<'a, SimClock>,
     );
-    fn run(&mut self, (entities, mut background_star, rigid_body, updater): Self::SystemData) {
+    fn run(
+        &mut self,
+        (entities, mut background_star, rigid_body, updater, clock): Self::SystemData,
+    ) {
         let mut body_vec = Vec::new();
         for (star, body) in (&background_star, &rigid_body).join() {
             body_vec.push(body.clone());
         }
-        body_vec = update_bodies(body_vec);
+        // Stepped with the ticks like the planets, each by the real time a tick stands for at
+        // the current warp, so the stars keep their screen speed, stop while paused and run
+        // backwards when reversed
+        let dt = (clock.step_dt().in_base().to_float() / clock.speed()) as f32;
+        for _ in 0..clock.ticks_this_frame() {
+            body_vec = update_bodies(body_vec, dt);
+        }
         for (i, (entity, star, body)) in (&entities, &mut background_star, &rigid_body)
             .join()
             .enumerate()
@@ -449,77 +449,151 @@ struct PlaceholderColor {
 }
 #[derive(Clone, Debug)]
 struct OrbitMetadata {
-    gravitational_constant: f32,
     eccentricity: f32,
-    major_axis: f32,
-    theta: f32,
+    major_axis: Length,
+    // One standard deviation of the measured major axis
+    major_axis_error: Length,
+    // Radians, see `gravity::Orbit`
+    argument_of_periapsis: f32,
+    true_anomaly: f32,
+    mass: Mass,
+    // What the planet orbits, the sun for every planet so far
+    central_mass: Mass,
     color: Vec<Color>,
 }
+// Angles are in degrees and the mass in Earth masses
 #[derive(Deserialize, Serialize, Clone, Debug)]
 struct OrbitMetadataSave {
-    gravitational_constant: f32,
     eccentricity: f32,
     major_axis: f32,
+    #[serde(default)]
+    major_axis_error: f32,
+    #[serde(default)]
+    argument_of_periapsis: f32,
+    #[serde(default)]
+    true_anomaly: f32,
+    mass: f32,
     name: String,
 }
 impl OrbitMetadataSave {
     fn to_unsavable(&self) -> OrbitMetadata {
-        let mut color_vector = Vec::new();
-        color_vector.push(Color::new(
+        let color_vector = vec![Color::new(
             rand::gen_range(0.0, 1.0),
             rand::gen_range(0.0, 1.0),
             rand::gen_range(0.0, 1.0),
             1.0,
-        ));
+        )];
         OrbitMetadata::new(
-            self.gravitational_constant,
             self.eccentricity,
-            map_world_to_screen_space(BigNumber::new_d(ASTRONOMICAL_UNIT) * self.major_axis),
+            Length::new(
+                BigNumber::new_d(self.major_axis),
+                LengthUnit::AstronomicalUnit,
+            ),
+            Mass::new(BigNumber::new_d(self.mass), MassUnit::EarthMass),
             color_vector,
         )
+        .with_major_axis_error(Length::new(
+            BigNumber::new_d(self.major_axis_error),
+            LengthUnit::AstronomicalUnit,
+        ))
+        .with_orientation(
+            self.argument_of_periapsis.to_radians(),
+            self.true_anomaly.to_radians(),
+        )
     }
 }
 impl OrbitMetadata {
-    fn new(
-        gravitational_constant: f32,
-        eccentricity: f32,
-        major_axis: f32,
-        color: Vec<Color>,
-    ) -> Self {
+    fn new(eccentricity: f32, major_axis: Length, mass: Mass, color: Vec<Color>) -> Self {
         OrbitMetadata {
-            gravitational_constant,
             eccentricity,
             major_axis,
+            major_axis_error: Length::new(BigNumber::new_d(0.0), LengthUnit::Meter),
+            argument_of_periapsis: 0.0,
+            true_anomaly: 0.0,
+            mass,
+            central_mass: Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass),
             color,
-            theta: 0.0,
+        }
+    }
+    fn with_major_axis_error(mut self, error: Length) -> Self {
+        self.major_axis_error = error;
+        self
+    }
+    fn with_orientation(mut self, argument_of_periapsis: f32, true_anomaly: f32) -> Self {
+        self.argument_of_periapsis = argument_of_periapsis;
+        self.true_anomaly = true_anomaly;
+        self
+    }
+    fn major_axis_in_au(&self) -> Uncertain {
+        Uncertain::new(
+            self.major_axis.in_unit(LengthUnit::AstronomicalUnit),
+            self.major_axis_error.in_unit(LengthUnit::AstronomicalUnit),
+        )
+    }
+    // Kepler's third law in years, from the semi-major axis in AU and the central mass in
+    // solar masses
+    fn period_in_years(&self) -> Uncertain {
+        ((self.major_axis_in_au() / 2.0).powi(3) / self.central_mass.in_unit(MassUnit::SolarMass))
+            .sqrt()
+    }
+    // Where the planet starts, the simulation takes it from there
+    fn orbit(&self) -> Orbit {
+        Orbit {
+            semi_major_axis: self.major_axis.cast() / 2.0,
+            eccentricity: self.eccentricity as f64,
+            argument_of_periapsis: self.argument_of_periapsis as f64,
+            true_anomaly: self.true_anomaly as f64,
+        }
+    }
+}
+impl<'a> System<'a> for SimulateGravity {
+    type SystemData = (
+        ReadExpect<'a, SimClock>,
+        Read<'a, SelectedIntegrator>,
+        Write<'a, AdaptiveIntegration>,
+        WriteStorage<'a, Body>,
+    );
+    fn run(&mut self, (clock, selected_integrator, mut adaptive, mut body): Self::SystemData) {
+        let mut bodies = (&body).join().copied().collect::<Vec<Body>>();
+        match INTEGRATORS[selected_integrator.0] {
+            Stepping::Fixed(integrator) => {
+                adaptive.0 = None;
+                for _ in 0..clock.ticks_this_frame() {
+                    integrator.step(&mut bodies, clock.step_dt());
+                }
+            }
+            // The integrator keeps the state at the end of its last step, the bodies get the
+            // state between steps at the current tick. It only runs forwards
+            Stepping::Adaptive => {
+                let (start, integrator) = adaptive.0.get_or_insert_with(|| {
+                    (
+                        clock.tick() - clock.ticks_this_frame() as i64,
+                        DormandPrince::new(bodies, Tolerance::default()),
+                    )
+                });
+                bodies = integrator.advance_to(clock.time_at(clock.tick() - *start));
+            }
+        }
+        for (stored, updated) in (&mut body).join().zip(bodies) {
+            *stored = updated;
         }
     }
 }
 impl<'a> System<'a> for UpdatePlanetPositions {
-    type SystemData = (WriteStorage<'a, Planet>);
-    fn run(&mut self, (mut planet): Self::SystemData) {
-        for object in (&mut planet).join() {
-            let mut orbit_data = object.orbit_data.clone();
-            orbit_data.theta = (orbit_data.theta
-                + get_delta_theta(
-                    orbit_data.theta,
-                    orbit_data.eccentricity,
-                    orbit_data.major_axis,
-                    orbit_data.gravitational_constant,
-                ))
-                % (2.0 * PI);
-            let angled_vector = vec2(f32::cos(orbit_data.theta), -f32::sin(orbit_data.theta)).mul(
-                radius_of_ellipse_from_theta(
-                    orbit_data.theta,
-                    orbit_data.eccentricity,
-                    orbit_data.major_axis,
-                ),
-            );
-            object.position = BigVec2 {
-                x: map_screen_to_world_space(object.focus.x + angled_vector.x),
-                y: map_screen_to_world_space(object.focus.y + angled_vector.y),
-            };
-            object.orbit_data = orbit_data;
+    type SystemData = (
+        WriteStorage<'a, Planet>,
+        WriteStorage<'a, Sun>,
+        ReadStorage<'a, Body>,
+        ReadExpect<'a, SimClock>,
+    );
+    fn run(&mut self, (mut planet, mut sun, body, clock): Self::SystemData) {
+        // Drawn where the bodies are between the last tick and the next
+        let since_tick = clock.since_tick().in_base();
+        for (object, body) in (&mut planet, &body).join() {
+            object.position = world_position(body.position + body.velocity * since_tick);
+        }
+        for (object, body) in (&mut sun, &body).join() {
+            object.position = world_position(body.position + body.velocity * since_tick);
         }
     }
 }
@@ -529,8 +603,8 @@ impl<'a> System<'a> for DrawTextAbovePlanets {
         for object in planet.join() {
             draw_text(
                 &object.name,
-                map_world_to_screen_space(object.position.clone().x),
-                map_world_to_screen_space(object.position.clone().y) - 50.0,
+                map_world_to_screen_space(object.position.x),
+                map_world_to_screen_space(object.position.y) - 50.0,
                 32.0,
                 RED,
             );
@@ -538,8 +612,20 @@ impl<'a> System<'a> for DrawTextAbovePlanets {
     }
 }
 impl<'a> System<'a> for DisplayPlanetInformation {
-    type SystemData = (Write<'a, SelectedPlanet>, ReadStorage<'a, Planet>);
-    fn run(&mut self, (mut selected_planet, planet): Self::SystemData) {
+    type SystemData = (
+        Write<'a, SelectedPlanet>,
+        Write<'a, InfoNotation>,
+        ReadStorage<'a, Planet>,
+    );
+    fn run(&mut self, (mut selected_planet, mut info_notation, planet): Self::SystemData) {
+        if (is_key_pressed(KeyCode::N)) {
+            *info_notation = InfoNotation::new(match info_notation.notation() {
+                Notation::Haven => Notation::Scientific,
+                Notation::Scientific => Notation::Engineering,
+                Notation::Engineering => Notation::SI("m".to_string()),
+                Notation::SI(_) => Notation::Haven,
+            });
+        }
         let (mouse_x, mouse_y) = mouse_position();
         let mut identical = None;
         for object in planet.join() {
@@ -552,45 +638,251 @@ impl<'a> System<'a> for DisplayPlanetInformation {
             {
                 identical = Some((*object).clone());
             }
-            match selected_planet.0 {
-                Some(ref x) => {
-                    if (x.name == object.name) {
-                        identical = Some(object.clone());
-                    }
+            if let Some(ref x) = selected_planet.0 {
+                if (x.name == object.name) {
+                    identical = Some(object.clone());
                 }
-                None => {}
             }
         }
         selected_planet.0 = identical;
-        match selected_planet.0 {
-            Some(ref x) => {
-                let position = x.get_position();
-                draw_text(
-                    format!(
-                        "Coordinates: ({}, {})",
-                        match position.x.serialized {
-                            Format::Haven(x) => x,
-                            Format::Scientific(x) => x,
-                        },
-                        match position.y.serialized {
-                            Format::Haven(x) => x,
-                            Format::Scientific(x) => x,
-                        }
-                    )
-                    .as_str(),
-                    (0.8 * 1920.0) - FONT_SIZE * 15.0,
-                    FONT_SIZE * 1.0,
-                    FONT_SIZE,
-                    GREEN,
+        if let Some(ref x) = selected_planet.0 {
+            // World coordinates are in km
+            let position = x.get_position();
+            let [x_state, y_state] = &mut info_notation.0;
+            let policy = x_state.policy().clone();
+            let major_axis = match policy.preferred() {
+                Notation::SI(_) => format!(
+                    "{}",
+                    (x.orbit_data.major_axis_in_au()
+                        * LengthUnit::AstronomicalUnit.factor() as f32)
+                        .display_with(&policy)
+                ),
+                _ => format!(
+                    "{} {}",
+                    x.orbit_data.major_axis_in_au().display_with(&policy),
+                    LengthUnit::AstronomicalUnit.symbol()
+                ),
+            };
+            // SI has no prefixed years, so the period is always written with plain numbers
+            let period_policy = match policy.preferred() {
+                Notation::SI(_) => FormatPolicy::default().with_significant_digits(5),
+                _ => policy,
+            };
+            let period = x.orbit_data.period_in_years();
+            let (lower, upper) = period.bounds(2.0);
+            draw_text(
+                format!(
+                    "Coordinates: ({}, {})",
+                    format_length(Length::new(position.x, LengthUnit::Kilometer), x_state),
+                    format_length(Length::new(position.y, LengthUnit::Kilometer), y_state)
                 )
-            }
-            _ => {}
+                .as_str(),
+                (0.8 * 1920.0) - FONT_SIZE * 15.0,
+                FONT_SIZE * 1.0,
+                FONT_SIZE,
+                GREEN,
+            );
+            draw_text(
+                format!("Major axis: {}", major_axis).as_str(),
+                (0.8 * 1920.0) - FONT_SIZE * 15.0,
+                FONT_SIZE * 2.0,
+                FONT_SIZE,
+                GREEN,
+            );
+            draw_text(
+                format!(
+                    "Period: {} yr ({} to {} at 2 sigma)",
+                    period.display_with(&period_policy),
+                    period_policy.format(&lower),
+                    period_policy.format(&upper)
+                )
+                .as_str(),
+                (0.8 * 1920.0) - FONT_SIZE * 15.0,
+                FONT_SIZE * 3.0,
+                FONT_SIZE,
+                GREEN,
+            );
+        }
+    }
+}
+// SI writes the prefix with the unit ("149.6 Gm"), the other notations are written in AU
+fn format_length(length: Length, state: &mut FormatState) -> String {
+    match state.policy().preferred() {
+        Notation::SI(_) => state.format(&length.in_unit(LengthUnit::Meter)),
+        _ => format!(
+            "{} {}",
+            state.format(&length.in_unit(LengthUnit::AstronomicalUnit)),
+            LengthUnit::AstronomicalUnit.symbol()
+        ),
+    }
+}
+impl<'a> System<'a> for DisplaySimulationInformation {
+    type SystemData = (
+        Write<'a, SelectedIntegrator>,
+        Write<'a, TimeWarp>,
+        WriteExpect<'a, SimClock>,
+        Read<'a, AdaptiveIntegration>,
+    );
+    fn run(
+        &mut self,
+        (mut selected_integrator, mut time_warp, mut clock, adaptive): Self::SystemData,
+    ) {
+        if (is_key_pressed(KeyCode::I)) {
+            selected_integrator.0 = (selected_integrator.0 + 1) % INTEGRATORS.len();
+        }
+        if (is_key_pressed(KeyCode::Up)) {
+            time_warp.0 = (time_warp.0 + 1).min(MAX_TIME_WARP);
+        }
+        if (is_key_pressed(KeyCode::Down)) {
+            time_warp.0 = (time_warp.0 - 1).max(0);
+        }
+        if (is_key_pressed(KeyCode::Space)) {
+            let paused = clock.is_paused();
+            clock.set_paused(!paused);
+        }
+        if (is_key_pressed(KeyCode::Period)) {
+            clock.set_paused(true);
+            clock.step();
+        }
+        let reversible = INTEGRATORS[selected_integrator.0].is_reversible();
+        if (is_key_pressed(KeyCode::R) && reversible) {
+            let reversed = clock.is_reversed();
+            clock.set_reversed(!reversed);
+        }
+        if (!reversible) {
+            clock.set_reversed(false);
+        }
+        clock.set_speed(time_warp.speed());
+        draw_text(
+            format!("Integrator: {}", INTEGRATORS[selected_integrator.0].name()).as_str(),
+            FONT_SIZE,
+            screen_height() - FONT_SIZE,
+            FONT_SIZE,
+            GREEN,
+        );
+        let state = match (clock.is_paused(), clock.is_reversed()) {
+            (true, _) => " (paused)",
+            (false, true) => " (reversed)",
+            (false, false) => "",
+        };
+        draw_text(
+            format!(
+                "Time warp: {}{}, day {:.1}",
+                *time_warp,
+                state,
+                clock.elapsed().in_unit(TimeUnit::Day).to_float()
+            )
+            .as_str(),
+            FONT_SIZE,
+            screen_height() - FONT_SIZE * 2.0,
+            FONT_SIZE,
+            GREEN,
+        );
+        if let Some((_, ref integrator)) = adaptive.0 {
+            let statistics = integrator.statistics;
+            let policy = FormatPolicy::default().with_significant_digits(3);
+            let days = |dt: Option<Time<f64>>| match dt {
+                Some(dt) => policy.format(&dt.in_unit(TimeUnit::Day)),
+                None => "-".to_string(),
+            };
+            draw_text(
+                format!(
+                    "Steps: {} accepted, {} rejected, dt {} to {} days",
+                    statistics.accepted,
+                    statistics.rejected,
+                    days(statistics.min_dt),
+                    days(statistics.max_dt)
+                )
+                .as_str(),
+                FONT_SIZE,
+                screen_height() - FONT_SIZE * 3.0,
+                FONT_SIZE,
+                GREEN,
+            );
         }
-        ()
     }
 }
 #[derive(Default)]
 struct SelectedPlanet(Option<Planet>);
+// Cycled with I, the first one is the default
+const INTEGRATORS: [Stepping; 5] = [
+    Stepping::Fixed(&Yoshida4),
+    Stepping::Fixed(&VelocityVerlet),
+    Stepping::Fixed(&RungeKutta4),
+    Stepping::Fixed(&SemiImplicitEuler),
+    Stepping::Adaptive,
+];
+enum Stepping {
+    // One step a `SimClock` tick of `TICK_DAYS`, so a frame takes a whole number of them, at
+    // most `SimClock::MAX_TICKS_PER_FRAME`
+    Fixed(&'static dyn Integrator),
+    // Steps as long as `DormandPrince` finds them accurate enough, see `AdaptiveIntegration`
+    Adaptive,
+}
+impl Stepping {
+    fn name(&self) -> &'static str {
+        match self {
+            Stepping::Fixed(integrator) => integrator.name(),
+            Stepping::Adaptive => "Dormand-Prince (adaptive)",
+        }
+    }
+    // Whether the simulation can run backwards with it
+    fn is_reversible(&self) -> bool {
+        match self {
+            Stepping::Fixed(integrator) => integrator.is_reversible(),
+            Stepping::Adaptive => false,
+        }
+    }
+}
+// The power of ten the simulation runs faster than real time, changed with Up and Down. Space
+// pauses, "." steps a single tick and R runs the simulation backwards where the integrator
+// allows it
+struct TimeWarp(i32);
+impl TimeWarp {
+    // Simulated seconds per real second
+    fn speed(&self) -> f64 {
+        10.0_f64.powi(self.0)
+    }
+}
+impl fmt::Display for TimeWarp {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            0 => write!(formatter, "1x"),
+            _ => write!(formatter, "10^{}x", self.0),
+        }
+    }
+}
+// About 12 days a second
+impl Default for TimeWarp {
+    fn default() -> TimeWarp {
+        TimeWarp(6)
+    }
+}
+// Which of `INTEGRATORS` the gravity simulation steps with
+#[derive(Default)]
+struct SelectedIntegrator(usize);
+// Only while the adaptive integrator is selected, with the tick it started at
+#[derive(Default)]
+struct AdaptiveIntegration(Option<(i64, DormandPrince)>);
+// How the information panel writes numbers, cycled with N. Each coordinate keeps its own
+// state so it does not flicker between notations while a planet crosses a threshold
+struct InfoNotation([FormatState; 2]);
+impl InfoNotation {
+    fn new(notation: Notation) -> InfoNotation {
+        let policy = FormatPolicy::new(notation)
+            .with_significant_digits(5)
+            .with_hysteresis(1);
+        InfoNotation([FormatState::new(policy.clone()), FormatState::new(policy)])
+    }
+    fn notation(&self) -> &Notation {
+        self.0[0].policy().preferred()
+    }
+}
+impl Default for InfoNotation {
+    fn default() -> InfoNotation {
+        InfoNotation::new(Notation::Haven)
+    }
+}
 fn window_conf() -> Conf {
     Conf {
         window_title: "ORBITAL_SYSTEM".to_string(),
@@ -610,7 +902,16 @@ async fn main() -> io::Result<()> {
 
     world.register::<Renderable>();
     world.register::<RigidBody>();
+    world.register::<Body>();
     world.insert(SelectedPlanet(None));
+    world.insert(InfoNotation::default());
+    world.insert(SelectedIntegrator::default());
+    world.insert(AdaptiveIntegration::default());
+    world.insert(TimeWarp::default());
+    world.insert(SimClock::new(
+        Time::new(BigNumber::new_d(TICK_DAYS), TimeUnit::Day),
+        TimeWarp::default().speed(),
+    ));
     // Initialize Simulation
     let mut first_iteration = true;
     let mut color_lerp = ColorLerp;
@@ -618,39 +919,43 @@ async fn main() -> io::Result<()> {
     let mut destroy_background_stars = DestroyBackgroundStars;
     let mut add_background_stars = AddBackgroundStars;
     let mut update_background_stars = UpdateBackgroundStars;
+    let mut simulate_gravity = SimulateGravity;
     let mut update_planet_positions = UpdatePlanetPositions;
     let mut draw_text_above_planets = DrawTextAbovePlanets;
     let mut display_planet_information = DisplayPlanetInformation;
+    let mut display_simulation_information = DisplaySimulationInformation;
     let mut orbit_metadata = HashMap::new();
     orbit_metadata.insert(
         "Lubaitis".to_string(),
         OrbitMetadata::new(
-            3.7,
             0.0206,
-            map_world_to_screen_space(BigNumber::new_d(ASTRONOMICAL_UNIT) * 1.4),
+            Length::new(BigNumber::new_d(1.4), LengthUnit::AstronomicalUnit),
+            Mass::new(BigNumber::new_d(0.8), MassUnit::EarthMass),
             vec![GRAY, WHITE],
         ),
     );
     orbit_metadata.insert(
         "Nora U3".to_string(),
         OrbitMetadata::new(
-            23.1,
             0.8,
-            map_world_to_screen_space(BigNumber::new_d(ASTRONOMICAL_UNIT) * 2.5),
+            Length::new(BigNumber::new_d(2.5), LengthUnit::AstronomicalUnit),
+            Mass::new(BigNumber::new_d(17.0), MassUnit::EarthMass),
             vec![BROWN, ORANGE, WHITE],
-        ),
+        )
+        .with_orientation(120.0_f32.to_radians(), 40.0_f32.to_radians()),
     );
     orbit_metadata.insert(
         "Zerth RM8F".to_string(),
         OrbitMetadata::new(
-            8.7,
             0.37,
-            map_world_to_screen_space(BigNumber::new_d(ASTRONOMICAL_UNIT) * 4.0),
+            Length::new(BigNumber::new_d(4.0), LengthUnit::AstronomicalUnit),
+            Mass::new(BigNumber::new_d(1.0), MassUnit::EarthMass),
             vec![BLUE, PURPLE],
-        ),
+        )
+        .with_orientation(240.0_f32.to_radians(), 200.0_f32.to_radians()),
     );
     let data_base_path = Path::new("data_base");
-    let written_file = File::open(&data_base_path.join("written.json"))?;
+    let written_file = File::open(data_base_path.join("written.json"))?;
     let written_file_reader = BufReader::new(&written_file);
     let mut metadata = Vec::new();
     metadata.push(String::new());
@@ -659,22 +964,22 @@ async fn main() -> io::Result<()> {
         let mut unwrapped_line = line.unwrap();
         let indent_size = unwrapped_line.len() - unwrapped_line.trim_start().len();
         unwrapped_line = unwrapped_line.trim().to_string();
-        let mut vector_indent_index = 0;
-        if (indent_sizes.get(indent_size).is_some()) {
-            vector_indent_index = *indent_sizes.get(indent_size).unwrap();
-        } else {
-            vector_indent_index = indent_sizes.len();
-            indent_sizes.push(vector_indent_index);
-        }
+        let vector_indent_index = match indent_sizes.get(indent_size) {
+            Some(index) => *index,
+            None => {
+                indent_sizes.push(indent_sizes.len());
+                indent_sizes.len() - 1
+            }
+        };
         if (vector_indent_index == 1) {
             let mut last = metadata.last().unwrap().clone();
             metadata.pop();
             if (!unwrapped_line.contains("}")) {
-                last.push_str("{");
+                last.push('{');
                 metadata.push(last);
                 continue;
             }
-            last.push_str("}");
+            last.push('}');
             metadata.push(last);
             metadata.push(String::new());
         } else if (vector_indent_index == 2) {
@@ -688,18 +993,30 @@ async fn main() -> io::Result<()> {
         let orbit_object = Deserializer::from_str(string_data.trim()).into_iter::<Value>();
         for value in orbit_object {
             let unwrapped_value = value.unwrap();
-            let gravitational_constant = match (unwrapped_value.get("gravitational_constant")) {
+            let eccentricity = match (unwrapped_value.get("eccentricity")) {
                 Some(value) => value.as_f64().unwrap(),
                 None => 0.0,
             } as f32;
-            let eccentricity = match (unwrapped_value.get("eccentricity")) {
+            let major_axis = match (unwrapped_value.get("major_axis")) {
                 Some(value) => value.as_f64().unwrap(),
                 None => 0.0,
             } as f32;
-            let major_axis = match (unwrapped_value.get("major_axis")) {
+            let major_axis_error = match (unwrapped_value.get("major_axis_error")) {
+                Some(value) => value.as_f64().unwrap(),
+                None => 0.0,
+            } as f32;
+            let argument_of_periapsis = match (unwrapped_value.get("argument_of_periapsis")) {
                 Some(value) => value.as_f64().unwrap(),
                 None => 0.0,
             } as f32;
+            let true_anomaly = match (unwrapped_value.get("true_anomaly")) {
+                Some(value) => value.as_f64().unwrap(),
+                None => 0.0,
+            } as f32;
+            let mass = match (unwrapped_value.get("mass")) {
+                Some(value) => value.as_f64().unwrap(),
+                None => 1.0,
+            } as f32;
             let name = match (unwrapped_value.get("name")) {
                 Some(value) => value.as_str().unwrap(),
                 None => "L",
@@ -708,9 +1025,12 @@ async fn main() -> io::Result<()> {
             orbit_metadata.insert(
                 name.clone(),
                 (OrbitMetadataSave {
-                    gravitational_constant,
                     eccentricity,
                     major_axis,
+                    major_axis_error,
+                    argument_of_periapsis,
+                    true_anomaly,
+                    mass,
                     name,
                 })
                 .to_unsavable(),
@@ -725,58 +1045,63 @@ async fn main() -> io::Result<()> {
         }
         if first_iteration {
             first_iteration = false;
-            for (key, individual) in orbit_metadata.iter_mut() {
-                let semi_major_axis = individual.major_axis / 2.0;
-                let semi_minor_axis =
-                    semi_major_axis * f32::sqrt(1.0 - Real::powi(individual.eccentricity, 2));
-                let foci =
-                    f32::sqrt(Real::powi(semi_minor_axis, 2) + Real::powi(semi_major_axis, 2));
+            let sun = Body::new(Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass));
+            // Sorted, so the bodies are simulated in the same order on every run
+            let mut names = orbit_metadata.keys().cloned().collect::<Vec<String>>();
+            names.sort();
+            let mut bodies = vec![sun];
+            for name in names.iter() {
+                let individual = &orbit_metadata[name];
+                bodies.push(Body::orbiting(
+                    &sun,
+                    &individual.orbit(),
+                    individual.mass.cast(),
+                ));
+            }
+            gravity::to_center_of_mass_frame(&mut bodies);
+            for (name, body) in names.iter().zip(&bodies[1..]) {
+                let individual = &orbit_metadata[name];
                 world
                     .create_entity()
                     .with(Planet {
-                        position: BigVec2 {
-                            x: map_screen_to_world_space(
-                                (screen_width() / 2.0) + (semi_major_axis - foci),
-                            ),
-                            y: map_screen_to_world_space(screen_height() / 2.0),
-                        },
-                        focus: Vec2 {
-                            x: (screen_width() / 2.0),
-                            y: screen_height() / 2.0,
-                        },
+                        position: world_position(body.position),
                         radius: 12.0,
                         color: individual.color.clone(),
-                        current_color: *individual.color.get(0).unwrap(),
+                        current_color: *individual.color.first().unwrap(),
                         color_elapsed_time: 0.0,
-                        name: key.to_string(),
+                        name: name.to_string(),
                         orbit_data: individual.clone(),
                     })
+                    .with(*body)
                     .build();
             }
             world
                 .create_entity()
                 .with(Sun {
-                    position: BigVec2 {
-                        x: map_screen_to_world_space(screen_width() / 2.0),
-                        y: map_screen_to_world_space(screen_height() / 2.0),
-                    },
+                    position: world_position(bodies[0].position),
                     radius: 30.0,
                     color: vec![YELLOW, ORANGE],
                     current_color: YELLOW,
                     color_elapsed_time: 0.0,
                 })
+                .with(bodies[0])
                 .build();
             continue;
         }
-        destroy_background_stars.run_now(&mut world);
-        add_background_stars.run_now(&mut world);
+        destroy_background_stars.run_now(&world);
+        add_background_stars.run_now(&world);
 
+        world
+            .write_resource::<SimClock>()
+            .advance(get_frame_time() as f64);
         update_background_stars.run_now(&world);
+        simulate_gravity.run_now(&world);
         update_planet_positions.run_now(&world);
         color_lerp.run_now(&world);
         draw_object.run_now(&world);
         draw_text_above_planets.run_now(&world);
         display_planet_information.run_now(&world);
+        display_simulation_information.run_now(&world);
         world.maintain();
         next_frame().await;
     }
@@ -1,9 +1,10 @@
-use macroquad::{
-    math::{vec2, Vec2},
-    time::get_frame_time,
-};
+use macroquad::math::{vec2, Vec2};
 use specs::{Component, VecStorage};
 use std::ops::{Add, Mul};
+pub mod adaptive;
+pub mod clock;
+pub mod gravity;
+pub mod integrator;
 #[derive(Clone)]
 pub enum AccelerationType {
     Constant,
@@ -32,17 +33,15 @@ impl Default for RigidBody {
     }
 }
 
-pub fn update_bodies(mut bodies: Vec<RigidBody>) -> Vec<RigidBody> {
+// `dt` in seconds
+pub fn update_bodies(mut bodies: Vec<RigidBody>, dt: f32) -> Vec<RigidBody> {
     let mut owned_vec = Vec::new();
     for body in bodies.iter_mut() {
-        match body.acceleration_type {
-            AccelerationType::Linear => {
-                body.acceleration += (body.acceleration.mul(1.0 / body.update as f32));
-            }
-            _ => {}
+        if let AccelerationType::Linear = body.acceleration_type {
+            body.acceleration += (body.acceleration.mul(1.0 / body.update as f32));
         }
-        body.velocity = body.velocity.add(body.acceleration * get_frame_time());
-        body.position = body.position.add(body.velocity * get_frame_time());
+        body.velocity = body.velocity.add(body.acceleration * dt);
+        body.position = body.position.add(body.velocity * dt);
         body.update += 1;
         owned_vec.push(body.clone());
     }
@@ -0,0 +1,385 @@
+use super::gravity::{accelerations, Body};
+use crate::big_number::{BigNumber, BigVec2};
+use crate::quantity::{Length, LengthUnit, Time, Velocity, VelocityUnit};
+// Dormand-Prince 5(4): a fifth order Runge-Kutta step with an embedded fourth order one, whose
+// difference estimates the error. Steps that miss the tolerance are retried shorter and the next
+// step is sized from the error of the last, so close approaches get short steps and the long
+// stretches in between get long ones. The integrator owns the state and takes whole steps; the
+// bodies between steps come from the interpolant the method provides ("dense output").
+//
+// Coefficients from Dormand and Prince (1980) and Hairer, Norsett and Wanner, "Solving Ordinary
+// Differential Equations I", the dense output from the latter's DOPRI5. Gravity does not depend
+// on time, so the nodes the stages are evaluated at are left out
+const A: [&[f64]; 6] = [
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+    ],
+    &[
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+    ],
+    // Also the fifth order weights, so the last stage is the derivative at the new state
+    &[
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+// Fifth minus fourth order weights
+const ERROR: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+const DENSE: [f64; 7] = [
+    -12715105075.0 / 11282082432.0,
+    0.0,
+    87487479700.0 / 32700410799.0,
+    -10690763975.0 / 1880347072.0,
+    701980252875.0 / 199316789632.0,
+    -1453857185.0 / 822651844.0,
+    69997945.0 / 29380423.0,
+];
+// Step size changes per step are kept within these, with some margin below the optimal size
+const SAFETY: f64 = 0.9;
+const MIN_FACTOR: f64 = 0.2;
+const MAX_FACTOR: f64 = 5.0;
+// The error allowed per component is `position`/`velocity` plus `relative` times its size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub position: Length<f64>,
+    pub velocity: Velocity<f64>,
+    pub relative: f64,
+}
+impl Default for Tolerance {
+    fn default() -> Tolerance {
+        Tolerance {
+            position: Length::new(BigNumber::new_d(1.0), LengthUnit::Kilometer),
+            velocity: Velocity::new(BigNumber::new_d(1e-3), VelocityUnit::MeterPerSecond),
+            relative: 1e-9,
+        }
+    }
+}
+// Counted since the integrator was created, the step sizes are those of accepted steps
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StepStatistics {
+    pub accepted: u64,
+    pub rejected: u64,
+    pub min_dt: Option<Time<f64>>,
+    pub max_dt: Option<Time<f64>>,
+}
+impl StepStatistics {
+    fn record(&mut self, dt: BigNumber<f64>) {
+        self.accepted += 1;
+        let dt = Time::from_base(dt);
+        self.min_dt = Some(match self.min_dt {
+            Some(min_dt) if (min_dt.in_base() <= dt.in_base()) => min_dt,
+            _ => dt,
+        });
+        self.max_dt = Some(match self.max_dt {
+            Some(max_dt) if (max_dt.in_base() >= dt.in_base()) => max_dt,
+            _ => dt,
+        });
+    }
+}
+// The positions and velocities of every body, or their rates of change
+#[derive(Debug, Clone, PartialEq)]
+struct State {
+    positions: Vec<BigVec2<f64>>,
+    velocities: Vec<BigVec2<f64>>,
+}
+impl State {
+    fn of(bodies: &[Body]) -> State {
+        State {
+            positions: bodies.iter().map(|body| body.position).collect(),
+            velocities: bodies.iter().map(|body| body.velocity).collect(),
+        }
+    }
+    // The sum of every state times its weight
+    fn combine(terms: &[(&State, BigNumber<f64>)]) -> State {
+        let (first, _) = terms[0];
+        let mut sum = State {
+            positions: vec![BigVec2::zero(); first.positions.len()],
+            velocities: vec![BigVec2::zero(); first.velocities.len()],
+        };
+        for (state, weight) in terms {
+            if (weight.is_zero()) {
+                continue;
+            }
+            for i in 0..sum.positions.len() {
+                sum.positions[i] += state.positions[i] * *weight;
+                sum.velocities[i] += state.velocities[i] * *weight;
+            }
+        }
+        sum
+    }
+    // `bodies` moved to this state, keeping their masses
+    fn to_bodies(&self, bodies: &[Body]) -> Vec<Body> {
+        bodies
+            .iter()
+            .enumerate()
+            .map(|(i, body)| Body {
+                position: self.positions[i],
+                velocity: self.velocities[i],
+                mass: body.mass,
+            })
+            .collect()
+    }
+}
+// The rate of change of `bodies`
+fn rate(bodies: &[Body]) -> State {
+    State {
+        positions: bodies.iter().map(|body| body.velocity).collect(),
+        velocities: accelerations(bodies),
+    }
+}
+// The interpolant of one step, from `start` to `start` + `dt`
+#[derive(Debug, Clone)]
+struct DenseOutput {
+    start: BigNumber<f64>,
+    dt: BigNumber<f64>,
+    coefficients: [State; 5],
+}
+impl DenseOutput {
+    fn at(&self, time: BigNumber<f64>) -> State {
+        let theta = ((time - self.start) / self.dt).to_float();
+        let [first, second, third, fourth, fifth] = &self.coefficients;
+        let one = |x: f64| BigNumber::new_d(x);
+        State::combine(&[
+            (first, one(1.0)),
+            (second, one(theta)),
+            (third, one(theta * (1.0 - theta))),
+            (fourth, one(theta * theta * (1.0 - theta))),
+            (fifth, one(theta * theta * (1.0 - theta) * (1.0 - theta))),
+        ])
+    }
+}
+pub struct DormandPrince {
+    pub tolerance: Tolerance,
+    pub statistics: StepStatistics,
+    // The state after the last step, `time` seconds in, and its rate of change
+    bodies: Vec<Body>,
+    time: BigNumber<f64>,
+    rate: State,
+    // How far `advance_to` has been asked to go, never past `time`
+    target: BigNumber<f64>,
+    // The size of the next step to try
+    dt: BigNumber<f64>,
+    dense_output: Option<DenseOutput>,
+}
+impl DormandPrince {
+    pub fn new(bodies: Vec<Body>, tolerance: Tolerance) -> DormandPrince {
+        let rate = rate(&bodies);
+        let mut integrator = DormandPrince {
+            tolerance,
+            statistics: StepStatistics::default(),
+            time: BigNumber::new_d(0.0),
+            target: BigNumber::new_d(0.0),
+            dt: BigNumber::new_d(0.0),
+            dense_output: None,
+            rate,
+            bodies,
+        };
+        integrator.dt = integrator.initial_dt();
+        integrator
+    }
+    // Moves on to `time` after the start, which must not be earlier than the last call, and
+    // returns the bodies at that time. Steps are never cut short to land on it, so the result
+    // does not depend on how the time is split up
+    pub fn advance_to(&mut self, time: Time<f64>) -> Vec<Body> {
+        self.target = time.in_base();
+        while (self.time < self.target) {
+            self.step();
+        }
+        match (&self.dense_output) {
+            Some(dense_output) => dense_output.at(self.target).to_bodies(&self.bodies),
+            None => self.bodies.clone(),
+        }
+    }
+    // Root mean square of the components of `error`, each relative to what the tolerance allows
+    // given the sizes of the states before and after
+    fn error_norm(&self, error: &State, before: &State, after: &State) -> f64 {
+        let allowed = |absolute: BigNumber<f64>, a: &BigVec2<f64>, b: &BigVec2<f64>| {
+            let size = match (a.length() > b.length()) {
+                true => a.length(),
+                false => b.length(),
+            };
+            absolute + size * self.tolerance.relative
+        };
+        let mut sum = 0.0;
+        for i in 0..error.positions.len() {
+            let position = error.positions[i].length()
+                / allowed(
+                    self.tolerance.position.in_base(),
+                    &before.positions[i],
+                    &after.positions[i],
+                );
+            let velocity = error.velocities[i].length()
+                / allowed(
+                    self.tolerance.velocity.in_base(),
+                    &before.velocities[i],
+                    &after.velocities[i],
+                );
+            sum += position.to_float().powi(2) + velocity.to_float().powi(2);
+        }
+        (sum / (2 * error.positions.len()) as f64).sqrt()
+    }
+    // A hundredth of the time the state takes to change by its own size
+    fn initial_dt(&self) -> BigNumber<f64> {
+        let state = State::of(&self.bodies);
+        let zero = State::combine(&[(&state, BigNumber::new_d(0.0))]);
+        let size = self.error_norm(&state, &zero, &zero);
+        let speed = self.error_norm(&self.rate, &zero, &zero);
+        match (size < 1e-5 || speed < 1e-5) {
+            true => BigNumber::new_d(1e-6),
+            false => BigNumber::new_d(0.01 * size / speed),
+        }
+    }
+    // Takes one step, retrying it shorter until the error is within the tolerance
+    fn step(&mut self) {
+        let before = State::of(&self.bodies);
+        loop {
+            let dt = self.dt;
+            let mut rates = vec![self.rate.clone()];
+            for weights in A {
+                let mut terms = vec![(&before, BigNumber::new_d(1.0))];
+                for (rate, weight) in rates.iter().zip(weights.iter()) {
+                    terms.push((rate, dt * *weight));
+                }
+                let stage = State::combine(&terms).to_bodies(&self.bodies);
+                rates.push(rate(&stage));
+            }
+            let after = State::combine(
+                &A[5]
+                    .iter()
+                    .zip(rates.iter())
+                    .map(|(weight, rate)| (rate, dt * *weight))
+                    .chain([(&before, BigNumber::new_d(1.0))])
+                    .collect::<Vec<_>>(),
+            );
+            let weighted = |weights: &[f64; 7]| {
+                State::combine(
+                    &weights
+                        .iter()
+                        .zip(rates.iter())
+                        .map(|(weight, rate)| (rate, dt * *weight))
+                        .collect::<Vec<_>>(),
+                )
+            };
+            let error = self.error_norm(&weighted(&ERROR), &before, &after);
+            let factor = match (error == 0.0) {
+                true => MAX_FACTOR,
+                false => (SAFETY * error.powf(-0.2)).clamp(MIN_FACTOR, MAX_FACTOR),
+            };
+            if (error > 1.0 || !error.is_finite()) {
+                self.statistics.rejected += 1;
+                self.dt = dt * factor.min(1.0);
+                continue;
+            }
+            let one = BigNumber::new_d(1.0);
+            let difference = State::combine(&[(&after, one), (&before, -one)]);
+            let third = State::combine(&[(&rates[0], dt), (&difference, -one)]);
+            let fourth = State::combine(&[(&difference, one), (&rates[6], -dt), (&third, -one)]);
+            self.dense_output = Some(DenseOutput {
+                start: self.time,
+                dt,
+                coefficients: [before, difference, third, fourth, weighted(&DENSE)],
+            });
+            self.statistics.record(dt);
+            self.time += dt;
+            self.bodies = after.to_bodies(&self.bodies);
+            self.rate = rates.swap_remove(6);
+            self.dt = dt * factor;
+            return;
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::gravity::{
+        to_center_of_mass_frame, total_energy, Orbit, GRAVITATIONAL_CONSTANT,
+    };
+    use crate::physics::integrator::{Integrator, Yoshida4};
+    use crate::quantity::{Mass, MassUnit, TimeUnit, Unit};
+    use std::f64::consts::PI;
+    // Like Gojo, 1 AU with e = 0.9, starting at apoapsis
+    fn eccentric_orbit() -> (Vec<Body>, BigNumber<f64>) {
+        let sun = Body::new(Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass));
+        let orbit = Orbit {
+            semi_major_axis: Length::new(BigNumber::new_d(1.0), LengthUnit::AstronomicalUnit),
+            eccentricity: 0.9,
+            argument_of_periapsis: 0.0,
+            true_anomaly: PI,
+        };
+        let planet = Body::orbiting(
+            &sun,
+            &orbit,
+            Mass::new(BigNumber::new_d(3.0), MassUnit::EarthMass),
+        );
+        let mut bodies = vec![sun, planet];
+        to_center_of_mass_frame(&mut bodies);
+        let a = LengthUnit::AstronomicalUnit.factor();
+        let mu = (sun.mass + planet.mass) * GRAVITATIONAL_CONSTANT;
+        let period = (BigNumber::new_d(4.0 * PI * PI * a * a * a) / mu).sqrt();
+        (bodies, period)
+    }
+    #[test]
+    fn shortens_steps_at_periapsis() {
+        let (bodies, period) = eccentric_orbit();
+        let start = bodies[1].position - bodies[0].position;
+        let energy = total_energy(&bodies);
+        let mut integrator = DormandPrince::new(bodies, Tolerance::default());
+        let end = integrator.advance_to(Time::from_base(period * 10.0));
+        let offset = end[1].position - end[0].position;
+        assert!((offset.distance(&start) / start.length()).to_float() < 1e-4);
+        assert!(((total_energy(&end) - energy) / energy).abs().to_float() < 1e-6);
+        let statistics = integrator.statistics;
+        let (min_dt, max_dt) = (statistics.min_dt.unwrap(), statistics.max_dt.unwrap());
+        assert!((max_dt.in_base() / min_dt.in_base()).to_float() > 20.0);
+        assert!(statistics.rejected > 0);
+        // A fixed step would need tens of thousands to get through periapsis this well
+        assert!(statistics.accepted < 2000, "{:?}", statistics);
+    }
+    #[test]
+    fn interpolates_between_steps() {
+        let (bodies, period) = eccentric_orbit();
+        let mut reference = bodies.clone();
+        let mut integrator = DormandPrince::new(bodies, Tolerance::default());
+        // Frames much shorter than the steps around apoapsis
+        let frame = Time::from_base(period / 2000.0);
+        for frame_count in 1..=100 {
+            let interpolated =
+                integrator.advance_to(Time::from_base(frame.in_base() * frame_count as f64));
+            for _ in 0..10 {
+                Yoshida4.step(&mut reference, Time::from_base(frame.in_base() / 10.0));
+            }
+            let error = interpolated[1].position.distance(&reference[1].position);
+            assert!(error.to_float() < 10_000.0, "frame {}", frame_count);
+        }
+        let days = integrator.statistics.max_dt.unwrap().in_unit(TimeUnit::Day);
+        assert!(days.to_float() > frame.in_unit(TimeUnit::Day).to_float() * 5.0);
+        // The steps are the same however the time is split into frames
+        let (bodies, _) = eccentric_orbit();
+        let mut whole = DormandPrince::new(bodies, Tolerance::default());
+        whole.advance_to(Time::from_base(frame.in_base() * 100.0));
+        assert_eq!(whole.statistics, integrator.statistics);
+        assert_eq!(whole.bodies, integrator.bodies);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::quantity::Time;
+// Simulated time, advanced in ticks of a fixed length however long the frames take. The physics
+// reads nothing else, so a scenario goes through the same states on every run and at any frame
+// rate; only how many ticks land in each frame changes. Faster speeds take more ticks a frame
+// rather than longer ones, so the integrators stay as stable at any speed.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    // Simulated time per tick
+    dt: Time<f64>,
+    // Simulated seconds per real second
+    speed: f64,
+    paused: bool,
+    // Ticks count down and go back in time, which only reversible integrators can follow
+    reversed: bool,
+    // Single steps asked for with `step`, taken on the next frame even while paused
+    pending_steps: u64,
+    // Simulated seconds not ticked yet, less than one tick
+    accumulator: f64,
+    tick: i64,
+    ticks_this_frame: u64,
+}
+impl SimClock {
+    // A frame that would take more ticks than this drops the rest, so one slow frame does not
+    // make the next one slower still
+    pub const MAX_TICKS_PER_FRAME: u64 = 10_000;
+    pub fn new(dt: Time<f64>, speed: f64) -> SimClock {
+        SimClock {
+            dt,
+            speed,
+            paused: false,
+            reversed: false,
+            pending_steps: 0,
+            accumulator: 0.0,
+            tick: 0,
+            ticks_this_frame: 0,
+        }
+    }
+    // Moves the clock on by a frame of `frame_time` real seconds and returns how many ticks the
+    // physics should take for it
+    pub fn advance(&mut self, frame_time: f64) -> u64 {
+        let dt = self.dt.in_base().to_float();
+        if (!self.paused) {
+            self.accumulator += frame_time * self.speed;
+        }
+        let ticks = (self.accumulator / dt).floor() + self.pending_steps as f64;
+        self.pending_steps = 0;
+        self.ticks_this_frame = match (ticks > Self::MAX_TICKS_PER_FRAME as f64) {
+            true => {
+                self.accumulator = 0.0;
+                Self::MAX_TICKS_PER_FRAME
+            }
+            false => {
+                self.accumulator -= (self.accumulator / dt).floor() * dt;
+                ticks as u64
+            }
+        };
+        self.tick += match (self.reversed) {
+            true => -(self.ticks_this_frame as i64),
+            false => self.ticks_this_frame as i64,
+        };
+        self.ticks_this_frame
+    }
+    // The length of a tick
+    pub fn dt(&self) -> Time<f64> {
+        self.dt
+    }
+    // What each tick adds to the simulated time, negative while reversed
+    pub fn step_dt(&self) -> Time<f64> {
+        match (self.reversed) {
+            true => Time::from_base(-self.dt.in_base()),
+            false => self.dt,
+        }
+    }
+    pub fn speed(&self) -> f64 {
+        self.speed
+    }
+    pub fn set_speed(&mut self, speed: f64) {
+        self.speed = speed;
+    }
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+    pub fn is_reversed(&self) -> bool {
+        self.reversed
+    }
+    pub fn set_reversed(&mut self, reversed: bool) {
+        self.reversed = reversed;
+    }
+    // One more tick on the next frame
+    pub fn step(&mut self) {
+        self.pending_steps += 1;
+    }
+    pub fn tick(&self) -> i64 {
+        self.tick
+    }
+    pub fn ticks_this_frame(&self) -> u64 {
+        self.ticks_this_frame
+    }
+    // Simulated time at `tick`, computed from the count so it does not gather rounding errors
+    pub fn time_at(&self, tick: i64) -> Time<f64> {
+        Time::from_base(self.dt.in_base() * tick as f64)
+    }
+    pub fn elapsed(&self) -> Time<f64> {
+        self.time_at(self.tick)
+    }
+    // How far the frame is into the next tick, from 0 to 1, for drawing between ticks
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / self.dt.in_base().to_float()
+    }
+    // The simulated time from the last tick to the frame, negative while reversed
+    pub fn since_tick(&self) -> Time<f64> {
+        Time::from_base(self.step_dt().in_base() * self.alpha())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_number::BigNumber;
+    use crate::physics::gravity::{to_center_of_mass_frame, Body, Orbit};
+    use crate::physics::integrator::{Integrator, Yoshida4};
+    use crate::quantity::{Length, LengthUnit, Mass, MassUnit, TimeUnit};
+    fn clock() -> SimClock {
+        // Two days a tick, 30 days a second
+        SimClock::new(
+            Time::new(BigNumber::new_d(2.0), TimeUnit::Day),
+            30.0 * 86_400.0,
+        )
+    }
+    #[test]
+    fn ticks_do_not_depend_on_frames() {
+        let mut steady = clock();
+        let mut uneven = clock();
+        // 1.03125 seconds each, 15.46875 ticks
+        for _ in 0..66 {
+            steady.advance(1.0 / 64.0);
+        }
+        for frame_time in [0.25, 0.125, 0.5, 0.125, 1.0 / 32.0] {
+            uneven.advance(frame_time);
+        }
+        assert_eq!(steady.tick(), 15);
+        assert_eq!(uneven.tick(), 15);
+        assert!((steady.alpha() - 0.46875).abs() < 1e-9);
+        assert!((uneven.alpha() - 0.46875).abs() < 1e-9);
+        uneven.advance(1.0 / 64.0);
+        assert_eq!(uneven.ticks_this_frame(), 0);
+        let days = uneven.elapsed().in_unit(TimeUnit::Day);
+        assert!((days.to_float() - 30.0).abs() < 1e-9);
+        let mut stalled = clock();
+        stalled.advance(1e6);
+        assert_eq!(stalled.ticks_this_frame(), SimClock::MAX_TICKS_PER_FRAME);
+        assert_eq!(stalled.alpha(), 0.0);
+    }
+    #[test]
+    fn pauses_steps_and_reverses() {
+        let mut clock = clock();
+        clock.advance(0.25);
+        assert_eq!(clock.tick(), 3);
+        clock.set_paused(true);
+        clock.advance(1.0);
+        assert_eq!(clock.tick(), 3);
+        // The time left over before the pause is kept
+        assert!((clock.alpha() - 0.75).abs() < 1e-9);
+        clock.step();
+        clock.step();
+        clock.advance(1.0 / 60.0);
+        assert_eq!(clock.ticks_this_frame(), 2);
+        assert_eq!(clock.tick(), 5);
+        clock.set_paused(false);
+        clock.set_reversed(true);
+        assert_eq!(clock.step_dt().in_base(), -clock.dt().in_base());
+        clock.advance(0.25);
+        assert_eq!(clock.ticks_this_frame(), 4);
+        assert_eq!(clock.tick(), 1);
+        assert!(clock.since_tick().in_base().to_float() < 0.0);
+        clock.set_speed(clock.speed() * 10.0);
+        clock.advance(0.2);
+        assert_eq!(clock.tick(), -29);
+    }
+    // Runs frames of `frame_time` until `ticks` ticks and returns the bodies at that tick
+    fn simulate(frame_time: f64, ticks: u64) -> Vec<Body> {
+        let sun = Body::new(Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass));
+        let orbit = Orbit {
+            semi_major_axis: Length::new(BigNumber::new_d(1.0), LengthUnit::AstronomicalUnit),
+            eccentricity: 0.5,
+            argument_of_periapsis: 0.0,
+            true_anomaly: 0.0,
+        };
+        let earth = Body::orbiting(
+            &sun,
+            &orbit,
+            Mass::new(BigNumber::new_d(1.0), MassUnit::EarthMass),
+        );
+        let mut bodies = vec![sun, earth];
+        to_center_of_mass_frame(&mut bodies);
+        let mut clock = clock();
+        let mut tick = 0;
+        loop {
+            for _ in 0..clock.advance(frame_time) {
+                Yoshida4.step(&mut bodies, clock.dt());
+                tick += 1;
+                if (tick == ticks) {
+                    return bodies;
+                }
+            }
+        }
+    }
+    #[test]
+    fn same_trajectory_at_any_frame_rate() {
+        let slow = simulate(1.0 / 24.0, 500);
+        assert_eq!(slow, simulate(1.0 / 144.0, 500));
+        assert_eq!(slow, simulate(0.7, 500));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::big_number::{BigNumber, BigVec2};
+use crate::quantity::{Length, Mass};
+use specs::{Component, VecStorage};
+// Newtonian gravity between point masses in the plane. Every body pulls on every other one, so
+// perturbations and resonances come out of the simulation instead of being drawn on rails.
+// Everything is in SI units: m, m/s, kg and s.
+//
+// m^3 kg^-1 s^-2
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Body {
+    pub position: BigVec2<f64>,
+    pub velocity: BigVec2<f64>,
+    pub mass: BigNumber<f64>,
+}
+impl Component for Body {
+    type Storage = VecStorage<Self>;
+}
+// The shape of an orbit and where the body starts on it. Angles are in radians, counterclockwise
+// from the x axis; the periapsis is `argument_of_periapsis` and the body is `true_anomaly` past it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Orbit {
+    pub semi_major_axis: Length<f64>,
+    pub eccentricity: f64,
+    pub argument_of_periapsis: f64,
+    pub true_anomaly: f64,
+}
+fn rotate(x: f64, y: f64, angle: f64) -> BigVec2<f64> {
+    let (sin, cos) = angle.sin_cos();
+    BigVec2::new(
+        BigNumber::new_d(x * cos - y * sin),
+        BigNumber::new_d(x * sin + y * cos),
+    )
+}
+impl Orbit {
+    // Position and velocity relative to the body orbited, for G (M + m) = `gravitational_parameter`
+    pub fn relative_state(
+        &self,
+        gravitational_parameter: BigNumber<f64>,
+    ) -> (BigVec2<f64>, BigVec2<f64>) {
+        let e = self.eccentricity;
+        let (sin, cos) = self.true_anomaly.sin_cos();
+        // Semi-latus rectum
+        let p = self.semi_major_axis.in_base() * (1.0 - e * e);
+        let radius = p / (1.0 + e * cos);
+        let speed = (gravitational_parameter / p).sqrt();
+        let direction = self.argument_of_periapsis;
+        (
+            rotate(cos, sin, direction) * radius,
+            rotate(-sin, e + cos, direction) * speed,
+        )
+    }
+}
+impl Body {
+    pub fn new(mass: Mass<f64>) -> Body {
+        Body {
+            position: BigVec2::zero(),
+            velocity: BigVec2::zero(),
+            mass: mass.in_base(),
+        }
+    }
+    // A body of `mass` placed on `orbit` around `central`
+    pub fn orbiting(central: &Body, orbit: &Orbit, mass: Mass<f64>) -> Body {
+        let gravitational_parameter = (central.mass + mass.in_base()) * GRAVITATIONAL_CONSTANT;
+        let (position, velocity) = orbit.relative_state(gravitational_parameter);
+        Body {
+            position: central.position + position,
+            velocity: central.velocity + velocity,
+            mass: mass.in_base(),
+        }
+    }
+    pub fn momentum(&self) -> BigVec2<f64> {
+        self.velocity * self.mass
+    }
+    pub fn kinetic_energy(&self) -> BigNumber<f64> {
+        self.velocity.length_squared() * self.mass / 2.0
+    }
+}
+// a_i = sum over j of G m_j (r_j - r_i) / |r_j - r_i|^3
+pub fn accelerations(bodies: &[Body]) -> Vec<BigVec2<f64>> {
+    let mut accelerations = vec![BigVec2::zero(); bodies.len()];
+    for i in 0..bodies.len() {
+        for j in (i + 1)..bodies.len() {
+            let offset = bodies[j].position - bodies[i].position;
+            let distance_squared = offset.length_squared();
+            // G / r^3, applied to each side with the other side's mass
+            let pull = BigNumber::new_d(GRAVITATIONAL_CONSTANT)
+                / (distance_squared * distance_squared.sqrt());
+            accelerations[i] += offset * (pull * bodies[j].mass);
+            accelerations[j] -= offset * (pull * bodies[i].mass);
+        }
+    }
+    accelerations
+}
+pub fn total_energy(bodies: &[Body]) -> BigNumber<f64> {
+    let mut energy = BigNumber::new_d(0.0);
+    for (i, body) in bodies.iter().enumerate() {
+        energy += body.kinetic_energy();
+        for other in &bodies[(i + 1)..] {
+            energy -= body.mass * other.mass * GRAVITATIONAL_CONSTANT
+                / body.position.distance(&other.position);
+        }
+    }
+    energy
+}
+// The z component, about the origin
+pub fn angular_momentum(bodies: &[Body]) -> BigNumber<f64> {
+    bodies
+        .iter()
+        .map(|body| body.position.cross(&body.momentum()))
+        .fold(BigNumber::new_d(0.0), |sum, x| sum + x)
+}
+// Moves the origin to the center of mass and takes out its velocity, so the system as a whole
+// stays where it is drawn
+pub fn to_center_of_mass_frame(bodies: &mut [Body]) {
+    let mut total_mass = BigNumber::new_d(0.0);
+    let mut weighted_position = BigVec2::zero();
+    let mut momentum = BigVec2::zero();
+    for body in bodies.iter() {
+        total_mass += body.mass;
+        weighted_position += body.position * body.mass;
+        momentum += body.momentum();
+    }
+    if (total_mass.is_zero()) {
+        return;
+    }
+    for body in bodies.iter_mut() {
+        body.position -= weighted_position / total_mass;
+        body.velocity -= momentum / total_mass;
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::integrator::{Integrator, SemiImplicitEuler};
+    use crate::quantity::{LengthUnit, MassUnit, Time, TimeUnit, Unit};
+    use std::f64::consts::PI;
+    fn sun_and_earth(eccentricity: f64) -> Vec<Body> {
+        let sun = Body::new(Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass));
+        let orbit = Orbit {
+            semi_major_axis: Length::new(BigNumber::new_d(1.0), LengthUnit::AstronomicalUnit),
+            eccentricity,
+            argument_of_periapsis: 0.3,
+            true_anomaly: 0.0,
+        };
+        let earth = Body::orbiting(
+            &sun,
+            &orbit,
+            Mass::new(BigNumber::new_d(1.0), MassUnit::EarthMass),
+        );
+        let mut bodies = vec![sun, earth];
+        to_center_of_mass_frame(&mut bodies);
+        bodies
+    }
+    fn relative_error(a: BigNumber<f64>, b: BigNumber<f64>) -> f64 {
+        ((a - b) / b).abs().to_float()
+    }
+    #[test]
+    fn starts_on_the_orbit() {
+        let bodies = sun_and_earth(0.5);
+        let offset = bodies[1].position - bodies[0].position;
+        let speed = (bodies[1].velocity - bodies[0].velocity).length();
+        // Periapsis at a (1 - e), moving at sqrt(mu / a (1 + e) / (1 - e)), 0.3 rad from x
+        let astronomical_unit = LengthUnit::AstronomicalUnit.factor();
+        let mu = (bodies[0].mass + bodies[1].mass) * GRAVITATIONAL_CONSTANT;
+        assert!(relative_error(offset.length(), BigNumber::new_d(0.5 * astronomical_unit)) < 1e-12);
+        assert!((offset.angle() - 0.3).abs() < 1e-12);
+        let expected = (mu / astronomical_unit * 3.0).sqrt();
+        assert!(relative_error(speed, expected) < 1e-12);
+        let velocity = bodies[1].velocity - bodies[0].velocity;
+        assert!(
+            (offset.dot(&velocity) / (offset.length() * speed))
+                .to_float()
+                .abs()
+                < 1e-12
+        );
+    }
+    #[test]
+    fn pulls_both_ways() {
+        let bodies = sun_and_earth(0.2);
+        let accelerations = accelerations(&bodies);
+        let net = accelerations[0] * bodies[0].mass + accelerations[1] * bodies[1].mass;
+        let scale = (accelerations[1] * bodies[1].mass).length();
+        assert!((net.length() / scale).to_float() < 1e-12);
+        let momentum = bodies[0].momentum() + bodies[1].momentum();
+        assert!((momentum.length() / bodies[1].momentum().length()).to_float() < 1e-12);
+    }
+    #[test]
+    fn closes_an_orbit_after_one_period() {
+        let mut bodies = sun_and_earth(0.3);
+        let start = bodies[1].position - bodies[0].position;
+        let energy = total_energy(&bodies);
+        let mu = (bodies[0].mass + bodies[1].mass) * GRAVITATIONAL_CONSTANT;
+        let a = LengthUnit::AstronomicalUnit.factor();
+        let period = (BigNumber::new_d(4.0 * PI * PI * a * a * a) / mu).sqrt();
+        let steps = 20_000;
+        let dt = Time::from_base(period / steps as f64);
+        for _ in 0..steps {
+            SemiImplicitEuler.step(&mut bodies, dt);
+        }
+        let end = bodies[1].position - bodies[0].position;
+        assert!((end.distance(&start) / start.length()).to_float() < 1e-2);
+        assert!(relative_error(total_energy(&bodies), energy) < 1e-3);
+        let days = Time::<f64>::from_base(period).in_unit(TimeUnit::Day);
+        assert!((days.to_float() - 365.25).abs() < 0.1);
+    }
+}
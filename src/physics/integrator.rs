@@ -0,0 +1,218 @@
+use super::gravity::{accelerations, Body};
+use crate::big_number::{BigNumber, BigVec2};
+use crate::quantity::Time;
+// Ways of advancing `Body`s under their mutual gravity by one step. The symplectic ones (every
+// one but RK4) keep the energy error bounded instead of letting it pile up orbit after orbit.
+pub trait Integrator: Send + Sync {
+    // `dt` may be negative, which runs the simulation backwards
+    fn step(&self, bodies: &mut [Body], dt: Time<f64>);
+    fn name(&self) -> &'static str;
+    // Whether a step of -dt undoes a step of dt, up to rounding
+    fn is_reversible(&self) -> bool;
+}
+// Velocities first, then positions with the new velocities. First order
+pub struct SemiImplicitEuler;
+// Leapfrog in kick-drift-kick form. Second order
+pub struct VelocityVerlet;
+// The classic fourth order Runge-Kutta. Accurate per step but not symplectic, so its energy
+// slowly drifts
+pub struct RungeKutta4;
+// Three leapfrog steps with weights chosen to cancel the third order error. Fourth order
+pub struct Yoshida4;
+fn drift(bodies: &mut [Body], dt: BigNumber<f64>) {
+    for body in bodies.iter_mut() {
+        body.position += body.velocity * dt;
+    }
+}
+fn kick(bodies: &mut [Body], dt: BigNumber<f64>) {
+    let accelerations = accelerations(bodies);
+    for (body, acceleration) in bodies.iter_mut().zip(accelerations) {
+        body.velocity += acceleration * dt;
+    }
+}
+impl Integrator for SemiImplicitEuler {
+    fn step(&self, bodies: &mut [Body], dt: Time<f64>) {
+        let dt = dt.in_base();
+        kick(bodies, dt);
+        drift(bodies, dt);
+    }
+    fn name(&self) -> &'static str {
+        "Semi-implicit Euler"
+    }
+    fn is_reversible(&self) -> bool {
+        false
+    }
+}
+impl Integrator for VelocityVerlet {
+    fn step(&self, bodies: &mut [Body], dt: Time<f64>) {
+        let dt = dt.in_base();
+        kick(bodies, dt / 2.0);
+        drift(bodies, dt);
+        kick(bodies, dt / 2.0);
+    }
+    fn name(&self) -> &'static str {
+        "Velocity Verlet"
+    }
+    fn is_reversible(&self) -> bool {
+        true
+    }
+}
+// The velocity and acceleration of every body
+type Derivative = (Vec<BigVec2<f64>>, Vec<BigVec2<f64>>);
+// The derivative of `bodies` moved by `derivative` * `dt`
+fn derivative(bodies: &[Body], derivative: Option<&Derivative>, dt: BigNumber<f64>) -> Derivative {
+    let mut moved = bodies.to_vec();
+    if let Some((velocities, accelerations)) = derivative {
+        for (i, body) in moved.iter_mut().enumerate() {
+            body.position += velocities[i] * dt;
+            body.velocity += accelerations[i] * dt;
+        }
+    }
+    (
+        moved.iter().map(|body| body.velocity).collect(),
+        accelerations(&moved),
+    )
+}
+impl Integrator for RungeKutta4 {
+    fn step(&self, bodies: &mut [Body], dt: Time<f64>) {
+        let dt = dt.in_base();
+        let k1 = derivative(bodies, None, dt);
+        let k2 = derivative(bodies, Some(&k1), dt / 2.0);
+        let k3 = derivative(bodies, Some(&k2), dt / 2.0);
+        let k4 = derivative(bodies, Some(&k3), dt);
+        for (i, body) in bodies.iter_mut().enumerate() {
+            body.position += (k1.0[i] + (k2.0[i] + k3.0[i]) * 2.0 + k4.0[i]) * (dt / 6.0);
+            body.velocity += (k1.1[i] + (k2.1[i] + k3.1[i]) * 2.0 + k4.1[i]) * (dt / 6.0);
+        }
+    }
+    fn name(&self) -> &'static str {
+        "Runge-Kutta 4"
+    }
+    fn is_reversible(&self) -> bool {
+        false
+    }
+}
+impl Integrator for Yoshida4 {
+    fn step(&self, bodies: &mut [Body], dt: Time<f64>) {
+        let dt = dt.in_base();
+        let cube_root = 2.0_f64.cbrt();
+        let outer = 1.0 / (2.0 - cube_root);
+        let inner = -cube_root / (2.0 - cube_root);
+        drift(bodies, dt * (outer / 2.0));
+        kick(bodies, dt * outer);
+        drift(bodies, dt * ((outer + inner) / 2.0));
+        kick(bodies, dt * inner);
+        drift(bodies, dt * ((outer + inner) / 2.0));
+        kick(bodies, dt * outer);
+        drift(bodies, dt * (outer / 2.0));
+    }
+    fn name(&self) -> &'static str {
+        "Yoshida 4"
+    }
+    fn is_reversible(&self) -> bool {
+        true
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::gravity::{
+        angular_momentum, to_center_of_mass_frame, total_energy, Orbit, GRAVITATIONAL_CONSTANT,
+    };
+    use crate::quantity::{Length, LengthUnit, Mass, MassUnit, Unit};
+    use std::f64::consts::PI;
+    const STEPS: usize = 100_000;
+    // 1 AU around the sun with e = 0.3, `steps_per_orbit` steps a period
+    fn two_body_orbit(steps_per_orbit: f64) -> (Vec<Body>, Time<f64>) {
+        let sun = Body::new(Mass::new(BigNumber::new_d(1.0), MassUnit::SolarMass));
+        let orbit = Orbit {
+            semi_major_axis: Length::new(BigNumber::new_d(1.0), LengthUnit::AstronomicalUnit),
+            eccentricity: 0.3,
+            argument_of_periapsis: 1.0,
+            true_anomaly: 0.5,
+        };
+        let earth = Body::orbiting(
+            &sun,
+            &orbit,
+            Mass::new(BigNumber::new_d(1.0), MassUnit::EarthMass),
+        );
+        let mut bodies = vec![sun, earth];
+        to_center_of_mass_frame(&mut bodies);
+        let a = LengthUnit::AstronomicalUnit.factor();
+        let mu = (sun.mass + earth.mass) * GRAVITATIONAL_CONSTANT;
+        let period = (BigNumber::new_d(4.0 * PI * PI * a * a * a) / mu).sqrt();
+        (bodies, Time::from_base(period / steps_per_orbit))
+    }
+    // The largest relative energy and angular momentum errors seen over `STEPS` steps
+    fn drift(integrator: &dyn Integrator, steps_per_orbit: f64) -> (f64, f64) {
+        let (mut bodies, dt) = two_body_orbit(steps_per_orbit);
+        let energy = total_energy(&bodies);
+        let angular = angular_momentum(&bodies);
+        let (mut energy_error, mut angular_error) = (0.0_f64, 0.0_f64);
+        for _ in 0..STEPS {
+            integrator.step(&mut bodies, dt);
+            energy_error =
+                energy_error.max(((total_energy(&bodies) - energy) / energy).abs().to_float());
+            angular_error = angular_error.max(
+                ((angular_momentum(&bodies) - angular) / angular)
+                    .abs()
+                    .to_float(),
+            );
+        }
+        (energy_error, angular_error)
+    }
+    #[test]
+    fn symplectic_integrators_keep_energy_bounded() {
+        // 200 steps an orbit, so 500 orbits
+        let (energy, angular) = drift(&SemiImplicitEuler, 200.0);
+        assert!(energy < 5e-2, "{}", energy);
+        assert!(angular < 1e-9, "{}", angular);
+        let (energy, angular) = drift(&VelocityVerlet, 200.0);
+        assert!(energy < 5e-3, "{}", energy);
+        assert!(angular < 1e-9, "{}", angular);
+        let (energy, angular) = drift(&Yoshida4, 200.0);
+        assert!(energy < 1e-5, "{}", energy);
+        assert!(angular < 1e-9, "{}", angular);
+    }
+    #[test]
+    fn runge_kutta_is_accurate_but_drifts() {
+        let (energy, angular) = drift(&RungeKutta4, 200.0);
+        assert!(energy < 1e-4, "{}", energy);
+        assert!(angular < 1e-4, "{}", angular);
+        // The error keeps growing, the last half of the run adds to the first
+        let (mut bodies, dt) = two_body_orbit(200.0);
+        let energy = total_energy(&bodies);
+        let mut errors = Vec::new();
+        for _ in 0..2 {
+            for _ in 0..(STEPS / 2) {
+                RungeKutta4.step(&mut bodies, dt);
+            }
+            errors.push(((total_energy(&bodies) - energy) / energy).abs().to_float());
+        }
+        assert!(errors[1] > errors[0] * 1.5, "{:?}", errors);
+    }
+    #[test]
+    fn reversible_integrators_retrace_their_steps() {
+        let integrators: [&dyn Integrator; 4] =
+            [&SemiImplicitEuler, &VelocityVerlet, &RungeKutta4, &Yoshida4];
+        for integrator in integrators {
+            let (start, dt) = two_body_orbit(100.0);
+            let mut bodies = start.clone();
+            for _ in 0..100 {
+                integrator.step(&mut bodies, dt);
+            }
+            for _ in 0..100 {
+                integrator.step(&mut bodies, Time::from_base(-dt.in_base()));
+            }
+            let error = (bodies[1].position.distance(&start[1].position)
+                / start[1].position.length())
+            .to_float();
+            assert_eq!(
+                error < 1e-9,
+                integrator.is_reversible(),
+                "{}",
+                integrator.name()
+            );
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use crate::big_number::format_policy::FormatPolicy;
+use crate::big_number::{BigNumber, Mantissa};
+use std::{fmt, marker::PhantomData, ops};
+// Physical quantities kept in SI base units (m, kg, s, m/s). The dimension is part of the
+// type, so adding a Length to a Mass or passing a Time where a Length is expected does not
+// compile; values only turn back into plain BigNumbers through `in_unit`.
+pub trait Unit: Copy + fmt::Debug {
+    // How many base units one of this unit is
+    fn factor(self) -> f64;
+    fn symbol(self) -> &'static str;
+}
+pub trait Dimension {
+    type Unit: Unit;
+    const BASE: Self::Unit;
+}
+pub mod dimension {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct Length;
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct Mass;
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct Time;
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+    pub struct Velocity;
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Meter,
+    Kilometer,
+    AstronomicalUnit,
+    LightYear,
+    Parsec,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MassUnit {
+    Kilogram,
+    SolarMass,
+    EarthMass,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Second,
+    Day,
+    // Julian year, 365.25 days
+    Year,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VelocityUnit {
+    MeterPerSecond,
+    KilometerPerSecond,
+    AstronomicalUnitPerYear,
+}
+const METERS_PER_ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+const METERS_PER_LIGHT_YEAR: f64 = 9_460_730_472_580_800.0;
+// 648000 / pi astronomical units
+const METERS_PER_PARSEC: f64 = 3.085_677_581_491_367e16;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+const SECONDS_PER_YEAR: f64 = 365.25 * SECONDS_PER_DAY;
+impl Unit for LengthUnit {
+    fn factor(self) -> f64 {
+        match self {
+            LengthUnit::Meter => 1.0,
+            LengthUnit::Kilometer => 1000.0,
+            LengthUnit::AstronomicalUnit => METERS_PER_ASTRONOMICAL_UNIT,
+            LengthUnit::LightYear => METERS_PER_LIGHT_YEAR,
+            LengthUnit::Parsec => METERS_PER_PARSEC,
+        }
+    }
+    fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Meter => "m",
+            LengthUnit::Kilometer => "km",
+            LengthUnit::AstronomicalUnit => "AU",
+            LengthUnit::LightYear => "ly",
+            LengthUnit::Parsec => "pc",
+        }
+    }
+}
+impl Unit for MassUnit {
+    fn factor(self) -> f64 {
+        match self {
+            MassUnit::Kilogram => 1.0,
+            MassUnit::SolarMass => 1.988_47e30,
+            MassUnit::EarthMass => 5.972_2e24,
+        }
+    }
+    fn symbol(self) -> &'static str {
+        match self {
+            MassUnit::Kilogram => "kg",
+            MassUnit::SolarMass => "M☉",
+            MassUnit::EarthMass => "M⊕",
+        }
+    }
+}
+impl Unit for TimeUnit {
+    fn factor(self) -> f64 {
+        match self {
+            TimeUnit::Second => 1.0,
+            TimeUnit::Day => SECONDS_PER_DAY,
+            TimeUnit::Year => SECONDS_PER_YEAR,
+        }
+    }
+    fn symbol(self) -> &'static str {
+        match self {
+            TimeUnit::Second => "s",
+            TimeUnit::Day => "d",
+            TimeUnit::Year => "yr",
+        }
+    }
+}
+impl Unit for VelocityUnit {
+    fn factor(self) -> f64 {
+        match self {
+            VelocityUnit::MeterPerSecond => 1.0,
+            VelocityUnit::KilometerPerSecond => 1000.0,
+            VelocityUnit::AstronomicalUnitPerYear => {
+                METERS_PER_ASTRONOMICAL_UNIT / SECONDS_PER_YEAR
+            }
+        }
+    }
+    fn symbol(self) -> &'static str {
+        match self {
+            VelocityUnit::MeterPerSecond => "m/s",
+            VelocityUnit::KilometerPerSecond => "km/s",
+            VelocityUnit::AstronomicalUnitPerYear => "AU/yr",
+        }
+    }
+}
+impl Dimension for dimension::Length {
+    type Unit = LengthUnit;
+    const BASE: LengthUnit = LengthUnit::Meter;
+}
+impl Dimension for dimension::Mass {
+    type Unit = MassUnit;
+    const BASE: MassUnit = MassUnit::Kilogram;
+}
+impl Dimension for dimension::Time {
+    type Unit = TimeUnit;
+    const BASE: TimeUnit = TimeUnit::Second;
+}
+impl Dimension for dimension::Velocity {
+    type Unit = VelocityUnit;
+    const BASE: VelocityUnit = VelocityUnit::MeterPerSecond;
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Quantity<D: Dimension, T: Mantissa = f32> {
+    // In the base unit of `D`
+    value: BigNumber<T>,
+    dimension: PhantomData<D>,
+}
+pub type Length<T = f32> = Quantity<dimension::Length, T>;
+pub type Mass<T = f32> = Quantity<dimension::Mass, T>;
+pub type Time<T = f32> = Quantity<dimension::Time, T>;
+pub type Velocity<T = f32> = Quantity<dimension::Velocity, T>;
+impl<D: Dimension, T: Mantissa> Quantity<D, T> {
+    pub fn new(value: BigNumber<T>, unit: D::Unit) -> Quantity<D, T> {
+        Quantity::from_base(value * BigNumber::from(unit.factor()))
+    }
+    pub fn from_base(value: BigNumber<T>) -> Quantity<D, T> {
+        Quantity {
+            value,
+            dimension: PhantomData,
+        }
+    }
+    pub fn in_unit(self, unit: D::Unit) -> BigNumber<T> {
+        self.value / BigNumber::from(unit.factor())
+    }
+    pub fn in_base(self) -> BigNumber<T> {
+        self.value
+    }
+    pub fn cast<U: Mantissa>(self) -> Quantity<D, U> {
+        Quantity::from_base(self.value.cast())
+    }
+    // "1.52 AU"
+    pub fn format_in(self, unit: D::Unit, policy: &FormatPolicy) -> String {
+        format!("{} {}", policy.format(&self.in_unit(unit)), unit.symbol())
+    }
+}
+impl<D: Dimension, T: Mantissa> fmt::Display for Quantity<D, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.value, D::BASE.symbol())
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Neg for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn neg(self) -> Quantity<D, T> {
+        Quantity::from_base(-self.value)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Add for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn add(self, other: Quantity<D, T>) -> Quantity<D, T> {
+        Quantity::from_base(self.value + other.value)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Sub for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn sub(self, other: Quantity<D, T>) -> Quantity<D, T> {
+        Quantity::from_base(self.value - other.value)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::AddAssign for Quantity<D, T> {
+    fn add_assign(&mut self, other: Quantity<D, T>) {
+        self.value += other.value;
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::SubAssign for Quantity<D, T> {
+    fn sub_assign(&mut self, other: Quantity<D, T>) {
+        self.value -= other.value;
+    }
+}
+// The ratio of two quantities of the same dimension has no unit
+impl<D: Dimension, T: Mantissa> ops::Div for Quantity<D, T> {
+    type Output = BigNumber<T>;
+    fn div(self, other: Quantity<D, T>) -> BigNumber<T> {
+        self.value / other.value
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Mul<BigNumber<T>> for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn mul(self, rhs: BigNumber<T>) -> Quantity<D, T> {
+        Quantity::from_base(self.value * rhs)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Div<BigNumber<T>> for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn div(self, rhs: BigNumber<T>) -> Quantity<D, T> {
+        Quantity::from_base(self.value / rhs)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Mul<T> for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn mul(self, rhs: T) -> Quantity<D, T> {
+        Quantity::from_base(self.value * rhs)
+    }
+}
+impl<D: Dimension, T: Mantissa> ops::Div<T> for Quantity<D, T> {
+    type Output = Quantity<D, T>;
+    fn div(self, rhs: T) -> Quantity<D, T> {
+        Quantity::from_base(self.value / rhs)
+    }
+}
+// Products and quotients that change dimension: `$a $op $b = $output`
+macro_rules! impl_dimension_operation {
+    ($($a:ident, $trait:ident, $method:ident, $b:ident => $output:ident);*) => {
+        $(
+            impl<T: Mantissa> ops::$trait<$b<T>> for $a<T> {
+                type Output = $output<T>;
+                fn $method(self, rhs: $b<T>) -> $output<T> {
+                    Quantity::from_base(ops::$trait::$method(self.value, rhs.value))
+                }
+            }
+        )*
+    };
+}
+impl_dimension_operation!(
+    Length, Div, div, Time => Velocity;
+    Length, Div, div, Velocity => Time;
+    Velocity, Mul, mul, Time => Length;
+    Time, Mul, mul, Velocity => Length
+);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::big_number::Notation;
+    fn number(x: f64) -> BigNumber<f64> {
+        BigNumber::new_d(x)
+    }
+    fn assert_close(actual: BigNumber<f64>, expected: f64) {
+        let relative = (actual.to_float() - expected).abs() / expected.abs();
+        assert!(relative < 1e-12, "{} is not {}", actual, expected);
+    }
+    #[test]
+    fn converts_between_units() {
+        let distance = Length::new(number(1.0), LengthUnit::Parsec);
+        assert_close(
+            distance.in_unit(LengthUnit::AstronomicalUnit),
+            206_264.806_247_096_4,
+        );
+        assert_close(
+            distance.in_unit(LengthUnit::LightYear),
+            3.261_563_777_167_433_6,
+        );
+        assert_close(
+            Length::new(number(1.0), LengthUnit::AstronomicalUnit).in_unit(LengthUnit::Kilometer),
+            149_597_870.7,
+        );
+        let sun = Mass::new(number(1.0), MassUnit::SolarMass);
+        assert_close(sun.in_unit(MassUnit::EarthMass), 332_954.355_178_996);
+        assert_close(
+            Time::new(number(1.0), TimeUnit::Year).in_unit(TimeUnit::Day),
+            365.25,
+        );
+        assert_eq!(
+            sun / Mass::new(number(2.0), MassUnit::SolarMass),
+            number(0.5)
+        );
+    }
+    #[test]
+    fn combines_dimensions() {
+        let orbit =
+            Length::new(number(1.0), LengthUnit::AstronomicalUnit) * 2.0 * std::f64::consts::PI;
+        let year = Time::new(number(1.0), TimeUnit::Year);
+        let speed: Velocity<f64> = orbit / year;
+        assert_close(
+            speed.in_unit(VelocityUnit::KilometerPerSecond),
+            29.785_254_365_591_54,
+        );
+        let travelled: Length<f64> = speed * Time::new(number(0.5), TimeUnit::Year);
+        assert_close(travelled / orbit, 0.5);
+        assert_close((orbit / speed).in_unit(TimeUnit::Day), 365.25);
+    }
+    #[test]
+    fn writes_values_with_units() {
+        let distance: Length = Length::new("1.52".parse().unwrap(), LengthUnit::AstronomicalUnit);
+        let policy = FormatPolicy::new(Notation::Scientific).with_significant_digits(3);
+        assert_eq!(
+            distance.format_in(LengthUnit::AstronomicalUnit, &policy),
+            "1.52x10^0 AU"
+        );
+        assert_eq!(distance.to_string(), "227.38876B m");
+        let earth: Mass = Mass::new(BigNumber::new_d(1.0), MassUnit::EarthMass);
+        assert_eq!(earth.to_string(), "5.9722SP kg");
+    }
+}